@@ -0,0 +1,27 @@
+#import bevy_sprite::mesh2d_vertex_output::VertexOutput
+
+struct CaptureLineMaterial {
+    glow_color: vec4<f32>,
+    // The fraction of the mesh's width that is the solid core of the line.
+    core_width: f32,
+};
+
+@group(2) @binding(0) var<uniform> material: CaptureLineMaterial;
+
+@fragment
+fn fragment(mesh: VertexOutput) -> @location(0) vec4<f32> {
+#ifdef VERTEX_COLORS
+    let line_color = mesh.color;
+#else
+    let line_color = vec4<f32>(1.0);
+#endif
+
+    // uv.y runs from 0 on one edge of the line to 1 on the other.
+    let from_center = abs(mesh.uv.y - 0.5) * 2.0;
+    let core = 1.0 - smoothstep(material.core_width * 0.85, material.core_width, from_center);
+    let glow = pow(1.0 - from_center, 2.0) * material.glow_color.a;
+
+    let color = mix(material.glow_color.rgb, line_color.rgb, core);
+    let alpha = max(core, glow) * line_color.a;
+    return vec4<f32>(color, alpha);
+}
@@ -1,13 +1,21 @@
+use crate::settings::Settings;
 use bevy::prelude::Projection::Orthographic;
 use bevy::prelude::*;
 use bevy::render::camera::ScalingMode;
 use bevy::render::view::RenderLayers;
 use bevy_simple_screen_boxing::CameraBox;
 
+/// The size of the world that is visible at once, regardless of the window's size.
+pub const RESOLUTION: Vec2 = Vec2::new(640., 360.);
+
+pub(crate) const CLEAR_COLOR: Color = Color::linear_rgb(204. / 255., 170. / 255., 92. / 255.);
+
 pub struct BevymonCameraPlugin;
 impl Plugin for BevymonCameraPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, setup);
+        app.add_systems(Startup, setup)
+            .add_systems(PostStartup, apply_palette)
+            .add_systems(Update, apply_palette.run_if(resource_changed::<Settings>));
     }
 }
 
@@ -22,8 +30,8 @@ struct PrimaryCamera;
 fn setup(mut commands: Commands) {
     let mut projection = OrthographicProjection::default_2d();
     projection.scaling_mode = ScalingMode::Fixed {
-        width: 640.,
-        height: 360.,
+        width: RESOLUTION.x,
+        height: RESOLUTION.y,
     };
     commands.spawn((
         Name::from("Main Camera"),
@@ -31,16 +39,12 @@ fn setup(mut commands: Commands) {
         RenderLayers::layer(0),
         Camera {
             order: 2,
-            clear_color: ClearColorConfig::Custom(Color::linear_rgb(
-                204. / 255.,
-                170. / 255.,
-                92. / 255.,
-            )),
+            clear_color: ClearColorConfig::Custom(CLEAR_COLOR),
             ..default()
         },
         Orthographic(projection.clone()),
         CameraBox::ResolutionIntegerScale {
-            resolution: (640., 360.).into(),
+            resolution: RESOLUTION,
             allow_imperfect_aspect_ratios: false,
         },
         PrimaryCamera,
@@ -53,17 +57,22 @@ fn setup(mut commands: Commands) {
         RenderLayers::layer(1),
         Camera {
             order: 1,
-            clear_color: ClearColorConfig::Custom(Color::linear_rgb(
-                204. / 255.,
-                170. / 255.,
-                92. / 255.,
-            )),
+            clear_color: ClearColorConfig::Custom(CLEAR_COLOR),
             ..default()
         },
         Orthographic(projection),
         CameraBox::ResolutionIntegerScale {
-            resolution: (640., 360.).into(),
+            resolution: RESOLUTION,
             allow_imperfect_aspect_ratios: false,
         },
     ));
 }
+
+fn apply_palette(settings: Res<Settings>, cameras: Query<&mut Camera>) {
+    let clear = settings.palette.colors().clear;
+    for mut camera in cameras {
+        if let ClearColorConfig::Custom(color) = &mut camera.clear_color {
+            *color = clear;
+        }
+    }
+}
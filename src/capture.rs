@@ -1,63 +1,191 @@
+pub mod assist;
+pub mod config;
+pub mod decay;
+mod effects;
+mod input;
 mod math;
-mod ui;
-
-use crate::camera::GizmoCamera;
+pub mod power;
+pub mod record;
+pub(crate) mod render;
+pub mod score;
+pub mod session;
+pub mod styler;
+#[cfg(test)]
+mod tests;
+pub(crate) mod ui;
+
+use crate::capture::assist::{AssistPlugin, LineShield};
+use crate::capture::config::{CaptureConfig, Containment};
+use crate::capture::decay::DecayPlugin;
+use crate::capture::effects::LoopEffectsPlugin;
+use crate::capture::input::CaptureInputPlugin;
 use crate::capture::math::{intersects, length};
+use crate::capture::power::{line_power, LoopShape};
+use crate::capture::record::{RecordPlugin, Recorder};
+use crate::capture::render::LineRenderPlugin;
+use crate::capture::score::ScorePlugin;
+use crate::capture::session::{
+    set_session_state, CaptureSession, CaptureSessionChanged, CapturedCreature, EndReason,
+    SessionPlugin, SessionState,
+};
+use crate::capture::styler::{styler_or_base, Styler, StylerPlugin, StylerSkin};
 use crate::capture::ui::CaptureUiPlugin;
+use crate::creature::types::CreatureType;
 use crate::creature::{CaptureProgress, CaptureRequirements};
-use crate::{Despawn, DespawnWith};
-use avian2d::position::Rotation;
+use crate::settings::{LineColorScheme, Settings, SettingsPlugin};
+use crate::{Despawn, DespawnPlugin, DespawnWith};
 use avian2d::prelude::{Collider, Collisions};
-use bevy::input::common_conditions::{input_just_pressed, input_just_released};
-use bevy::math::VectorSpace;
+use bevy::asset::AssetLoadFailedEvent;
 use bevy::prelude::*;
-use bevy::render::view::RenderLayers;
-use bevy::window::PrimaryWindow;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::Duration;
+
+pub const MAX_HEALTH: u32 = 4;
+
+const STYLER_ENERGY: f32 = 100.;
+
+const ENERGY_RECOVERY_PER_SECOND: f32 = 10.;
+
+/// Everything needed to capture creatures, including input, rendering and UI.
+///
+/// It needs nothing else from this game, so it can be added to another one on its own.
+///
+/// ```ignore
+/// app.add_plugins(
+///     CaptureCorePlugin::default()
+///         .with_line_width(8.)
+///         .with_containment(Containment::WholeCollider),
+/// );
+/// ```
+#[derive(Default)]
+pub struct CaptureCorePlugin {
+    config: CaptureConfig,
+    recording_directory: Option<PathBuf>,
+}
+
+impl CaptureCorePlugin {
+    pub fn with_line_width(mut self, width: f32) -> Self {
+        self.config.line_width = width;
+        self
+    }
+
+    /// Fades the line from `start` at its oldest point to `end` at its newest, instead of using
+    /// the player's settings.
+    pub fn with_line_colors(mut self, start: Color, end: Color) -> Self {
+        self.config.line_colors = Some((start, end));
+        self
+    }
+
+    pub fn with_max_line_length(mut self, max: Option<usize>) -> Self {
+        self.config.max_line_length = max;
+        self
+    }
+
+    pub fn with_containment(mut self, containment: Containment) -> Self {
+        self.config.containment = containment;
+        self
+    }
+
+    /// Asset paths of the sprites for the styler and the start of the line.
+    pub fn with_styler_sprites(
+        mut self,
+        styler: impl Into<String>,
+        start: impl Into<String>,
+    ) -> Self {
+        self.config.styler_sprite = styler.into();
+        self.config.start_sprite = start.into();
+        self
+    }
+
+    /// Saves every capture session to `directory` so that it can be replayed.
+    pub fn with_recording_directory(mut self, directory: impl Into<PathBuf>) -> Self {
+        self.recording_directory = Some(directory.into());
+        self
+    }
+}
 
-pub struct CapturePlugin;
-impl Plugin for CapturePlugin {
+impl Plugin for CaptureCorePlugin {
+    fn build(&self, app: &mut App) {
+        // Without the game's settings plugin to load them, the default settings are used.
+        if !app.is_plugin_added::<SettingsPlugin>() {
+            app.init_resource::<Settings>();
+        }
+        if !app.is_plugin_added::<DespawnPlugin>() {
+            app.add_plugins(DespawnPlugin);
+        }
+
+        app.insert_resource(self.config.clone())
+            .add_plugins(CaptureLogicPlugin)
+            .add_plugins(CaptureInputPlugin)
+            .add_plugins(CaptureUiPlugin)
+            .add_plugins(LineRenderPlugin)
+            .add_plugins(LoopEffectsPlugin)
+            .add_systems(Startup, load_assets)
+            .add_systems(
+                Update,
+                fall_back_to_standard_sprite.run_if(on_event::<AssetLoadFailedEvent<Image>>),
+            );
+
+        if let Some(directory) = &self.recording_directory {
+            app.insert_resource(Recorder::saving_to(directory.clone()));
+        }
+    }
+}
+
+/// The rules of capturing, without any input, rendering or UI.
+///
+/// Capture lines are driven by [`CapturePointPressed`], [`CapturePointMoved`] and
+/// [`CapturePointLifted`], so that this can run without a window.
+/// Those inputs, and [`CaptureCancelled`], are sent as events, while everything that happens
+/// to a capture because of them is only triggered for observers, such as [`CaptureSessionChanged`].
+/// It reads the player's [`Settings`] without adding them, leaving that to whatever adds it.
+pub(crate) struct CaptureLogicPlugin;
+impl Plugin for CaptureLogicPlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<CaptureLineConnected>()
             .add_event::<CaptureLineCollision>()
             .add_event::<CapturePointLifted>()
             .add_event::<CapturePointPressed>()
-            .add_event::<TakeDamage>()
-            .add_event::<CaptureFailed>()
-            .add_event::<CaptureSuccess>()
+            .add_event::<CapturePointMoved>()
+            .add_event::<CapturePointRestarted>()
+            .add_event::<CaptureCancelled>()
             .register_type::<CaptureLine>()
             .register_type::<Health>()
-            .register_type::<Assets>()
-            .init_resource::<Assets>()
-            .add_plugins(CaptureUiPlugin)
+            .register_type::<StylerSprites>()
+            .register_type::<StylerEnergy>()
+            .register_type::<LineEnergyDrain>()
+            .init_resource::<StylerSprites>()
+            .init_resource::<CaptureConfig>()
+            .init_resource::<LineEnergyDrain>()
+            .add_plugins(AssistPlugin)
+            .add_plugins(DecayPlugin)
+            .add_plugins(ScorePlugin)
+            .add_plugins(RecordPlugin)
+            .add_plugins(SessionPlugin)
+            .add_plugins(StylerPlugin)
             .add_systems(Startup, setup)
-            .add_systems(Update, adjust_linewidth)
-            .add_systems(Update, take_damage)
             .add_systems(
                 Update,
-                player_start_capture.run_if(
-                    input_just_pressed(MouseButton::Left)
-                        .or(input_just_pressed(MouseButton::Right)),
-                ),
+                recover_energy.run_if(not(any_with_component::<CaptureLine>)),
             )
             .add_systems(
                 Update,
-                player_stop_capture.run_if(
-                    input_just_released(MouseButton::Left)
-                        .or(input_just_released(MouseButton::Right)),
-                ),
+                start_capture.run_if(on_event::<CapturePointPressed>),
             )
             .add_systems(
                 Update,
                 (
+                    restart_capture_line.run_if(on_event::<CapturePointRestarted>),
                     add_points_to_capture_line,
                     trail_line,
                     follow_styler,
                     detect_capture_collision,
                     detect_complete.run_if(not(on_event::<CaptureLineCollision>)),
                     increase_capture_progress.run_if(on_event::<CaptureLineConnected>),
-                    connect_points,
                 )
                     .chain()
+                    .after(start_capture)
                     .run_if(any_with_component::<CaptureLine>),
             )
             .add_systems(
@@ -66,32 +194,26 @@ impl Plugin for CapturePlugin {
             )
             .add_systems(
                 Update,
-                emit_capture_events.run_if(on_event::<CapturePointLifted>),
+                (
+                    emit_capture_events.run_if(on_event::<CapturePointLifted>),
+                    end_cancelled_session.run_if(on_event::<CaptureCancelled>),
+                ),
             )
-            .add_systems(
-                Update,
-                (destroy_line, reset_capture_progress)
-                    .after(emit_capture_events)
-                    .chain()
-                    .run_if(
-                        on_event::<CapturePointLifted>
-                            .or(on_event::<CursorLeft>)
-                            .or(on_event::<CaptureLineCollision>),
-                    ),
-            );
+            .add_observer(take_damage)
+            .add_observer(destroy_line)
+            .add_observer(reset_capture_progress);
     }
 }
 
 fn detect_capture_collision(
     mut commands: Commands,
-    capture_line: Single<Entity, With<CaptureLine>>,
+    capture_line: Single<(Entity, &DespawnWith, Has<LineShield>), With<CaptureLine>>,
     capture_start: Single<Entity, With<CaptureLineStart>>,
     collisions: Collisions,
     mut collision_event: EventWriter<CaptureLineCollision>,
-    mut damage: EventWriter<TakeDamage>,
     damagable: Query<&Damage>,
 ) {
-    let capture_line = capture_line.into_inner();
+    let (capture_line, session, mut shielded) = capture_line.into_inner();
     let capture_start = capture_start.into_inner();
     for collision in collisions.collisions_with(capture_line) {
         let actual_collider = if collision.collider1 == capture_line {
@@ -104,20 +226,70 @@ fn detect_capture_collision(
             continue;
         }
 
+        if shielded {
+            shielded = false;
+            commands.entity(capture_line).remove::<LineShield>();
+            if damagable.contains(actual_collider) {
+                commands.entity(actual_collider).insert(Despawn);
+            }
+            continue;
+        }
+
         if let Ok(d) = damagable.get(actual_collider) {
-            damage.write(TakeDamage(d.0));
-            commands.trigger(TakeDamage(d.0));
+            commands.trigger(TakeDamage {
+                session: session.0,
+                amount: d.0,
+            });
         }
 
-        collision_event.write(CaptureLineCollision);
-        commands.trigger(CaptureLineCollision);
+        collision_event.write(CaptureLineCollision { session: session.0 });
+        set_session_state(
+            &mut commands,
+            session.0,
+            SessionState::Ended {
+                reason: EndReason::Hit,
+            },
+        );
     }
 }
 
-fn setup(asset_server: Res<AssetServer>, mut assets: ResMut<Assets>, mut commands: Commands) {
-    assets.styler = asset_server.load("Capture-Styler.png");
-    assets.styler_start = asset_server.load("captureline-start2.png");
-    commands.spawn(Health(4));
+fn setup(mut commands: Commands) {
+    commands.spawn((Health(MAX_HEALTH), StylerEnergy::new(STYLER_ENERGY)));
+}
+
+fn load_assets(
+    asset_server: Res<AssetServer>,
+    config: Res<CaptureConfig>,
+    mut sprites: ResMut<StylerSprites>,
+) {
+    sprites.stylers = StylerSkin::ALL
+        .into_iter()
+        .map(|skin| (skin, asset_server.load(skin.sprite(&config))))
+        .collect();
+    sprites.styler_start = asset_server.load(&config.start_sprite);
+}
+
+/// Uses the standard styler's sprite for any skin whose own sprite couldn't be loaded, such as when
+/// a game embedding this only has the sprite given to [`CaptureCorePlugin::with_styler_sprites`].
+fn fall_back_to_standard_sprite(
+    mut failed: EventReader<AssetLoadFailedEvent<Image>>,
+    mut sprites: ResMut<StylerSprites>,
+) {
+    let Some(standard) = sprites.stylers.get(&StylerSkin::Standard).cloned() else {
+        return;
+    };
+
+    for failed in failed.read() {
+        for (skin, sprite) in &mut sprites.stylers {
+            if *skin != StylerSkin::Standard && sprite.id() == failed.id {
+                warn!(
+                    "Using the standard sprite for the {skin:?} styler, as {}",
+                    failed.error
+                );
+                *sprite = standard.clone();
+            }
+        }
+    }
 }
 
 /// Represents when the user deliberately stops a capture
@@ -126,25 +298,40 @@ fn setup(asset_server: Res<AssetServer>, mut assets: ResMut<Assets>, mut command
 #[derive(Event, Debug)]
 pub struct CapturePointLifted;
 
-/// Represents when the user 'starts' a capture line.
+/// Represents when the user 'starts' a capture line, at the given point in the world.
 #[derive(Event, Debug)]
-pub struct CapturePointPressed;
+pub struct CapturePointPressed(pub Vec2);
 
-/// Represents when a capture failed
-///
-/// Whether it was 'ending' the capture too early, regardless of reason and includes the Entity
-/// that was attempted to be captured.
+/// Represents when the user moves the point they are drawing with to the given point in the world.
 #[derive(Event, Debug)]
-pub struct CaptureFailed(pub Entity);
+pub struct CapturePointMoved(pub Vec2);
 
-/// Represents when a Capture has succeeded.
+/// Represents when the user came back to a line they had stopped drawing without ending it, so the
+/// line starts over from the next point rather than jumping across the gap.
+///
+/// (IE: The cursor coming back into the window while [`CursorLeavePolicy::Wait`] was chosen.)
+///
+/// [`CursorLeavePolicy::Wait`]: crate::settings::CursorLeavePolicy::Wait
 #[derive(Event, Debug)]
-pub struct CaptureSuccess {
-    /// The entity that was captured.
-    pub captured: Entity,
+pub struct CapturePointRestarted;
 
-    /// The amount the 'overshot' occurred by.
-    pub overshot_by: usize,
+/// Represents when a capture was called off before it could be lifted, which resets progress
+/// without counting as a failure.
+#[derive(Event, Debug)]
+pub struct CaptureCancelled(pub CancelReason);
+
+/// Why a capture was cancelled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CancelReason {
+    /// The cursor left the window while [`CursorLeavePolicy::Cancel`] was chosen.
+    ///
+    /// [`CursorLeavePolicy::Cancel`]: crate::settings::CursorLeavePolicy::Cancel
+    CursorLeft,
+
+    /// The cursor didn't come back before the [`CursorLeavePolicy::Wait`] grace period ran out.
+    ///
+    /// [`CursorLeavePolicy::Wait`]: crate::settings::CursorLeavePolicy::Wait
+    GracePeriodExpired,
 }
 
 #[derive(Component, Reflect, Debug)]
@@ -155,23 +342,41 @@ struct CaptureLineStart;
 #[reflect(Component)]
 struct CaptureStyler;
 
+/// Represents when a creature's capture progress went up or down.
 #[derive(Event, Debug)]
-pub struct CaptureProgressChanged(pub Entity);
+pub struct CaptureProgressChanged {
+    /// The session being drawn when the progress changed. Progress can also decay between
+    /// sessions, when there is none.
+    pub session: Option<Entity>,
+    pub creature: Entity,
+
+    /// How much progress was gained, or lost if negative.
+    pub change: i32,
+}
 
+/// Represents when the capture line crossed itself during `session`, closing a loop.
 #[derive(Event, Debug)]
-struct CaptureLineConnected {
+pub struct CaptureLineConnected {
+    pub session: Entity,
     cull_to: (usize, (Vec2, Vec2)),
 }
 
+/// Represents when the capture line was hit by something, ending `session`.
 #[derive(Event, Debug)]
-struct CaptureLineCollision;
+pub struct CaptureLineCollision {
+    pub session: Entity,
+}
 
+/// Represents when the line was hit by something that does [`Damage`] during `session`.
 #[derive(Event, Debug)]
-struct TakeDamage(u32);
+pub struct TakeDamage {
+    pub session: Entity,
+    pub amount: u32,
+}
 
 #[derive(Component, Reflect, Debug)]
 #[reflect(Component)]
-pub struct Health(pub(crate) u32);
+pub struct Health(pub u32);
 
 #[derive(Component, Reflect, Debug)]
 #[reflect(Component)]
@@ -179,175 +384,302 @@ pub struct Damage(pub u32);
 
 #[derive(Component, Reflect, Debug)]
 #[reflect(Component)]
-struct Captured;
+pub struct Captured;
 
 #[derive(Component, Reflect, Debug)]
 #[reflect(Component)]
-struct CaptureLine {
-    line: Vec<Vec2>,
+pub struct CaptureLine {
+    pub line: Vec<Vec2>,
     start_color: Option<Color>,
     end_color: Option<Color>,
     max_line_length: Option<usize>,
-    width: f32,
+    pub width: f32,
+
+    /// How far the styler has moved while drawing, including points that have since been dropped.
+    drawn_length: f32,
+
+    /// When each point of `line` was drawn, as time elapsed since startup.
+    drawn_at: Vec<Duration>,
 }
 
+/// A place where a [`CaptureLine`] crosses itself.
+#[derive(Debug, Clone, Copy)]
+pub struct LineIntersection {
+    /// The index of the earlier of the two segments that cross.
+    pub segment: usize,
+
+    /// The index of the later of the two segments that cross.
+    pub other: usize,
+
+    /// The start and end of the earlier segment.
+    pub points: (Vec2, Vec2),
+
+    /// Where the segments, or their width offsets, cross.
+    pub at: Vec2,
+}
+
+impl CaptureLine {
+    /// Copies of a segment shifted by half the line's width, so that parts of the line that only
+    /// overlap still count as crossing.
+    pub fn width_offsets(&self, segment: (&Vec2, &Vec2)) -> [(Vec2, Vec2); 2] {
+        [
+            (segment.0 + self.width / 2., segment.1 + self.width / 2.),
+            (segment.0 - self.width / 2., segment.1 - self.width / 2.),
+        ]
+    }
+
+    /// Every place where the line crosses itself, ignoring segments that are next to each other.
+    pub fn intersections(&self) -> Vec<LineIntersection> {
+        if self.line.is_empty() {
+            return vec![];
+        }
+
+        let mut intersections = vec![];
+        let points = self.line.iter().zip(self.line[1..].iter());
+        for (i, first) in points.clone().enumerate() {
+            for (j, second) in points.clone().enumerate().skip(i) {
+                if second.0 == first.1 {
+                    continue;
+                }
+
+                let [second_w1, second_w2] = self.width_offsets(second);
+                let [first_w1, first_w2] = self.width_offsets(first);
+
+                let at = intersects(second, first)
+                    .or_else(|| {
+                        intersects((&second_w1.0, &second_w1.1), (&first_w1.0, &first_w1.1))
+                    })
+                    .or_else(|| {
+                        intersects((&second_w2.0, &second_w2.1), (&first_w2.0, &first_w2.1))
+                    });
+
+                if let Some(at) = at {
+                    intersections.push(LineIntersection {
+                        segment: i,
+                        other: j,
+                        points: (*first.0, *first.1),
+                        at,
+                    });
+                }
+            }
+        }
+        intersections
+    }
+
+    /// The points of the loop closed this frame, and when the first of them was drawn, given the
+    /// segments found crossing. Every crossing found in a frame belongs to the same loop, which
+    /// starts just after the earliest of them.
+    pub fn closed_loop(
+        &self,
+        segments: impl IntoIterator<Item = usize>,
+    ) -> Option<(&[Vec2], Duration)> {
+        let start = segments.into_iter().min()? + 1;
+        Some((self.line.get(start..)?, *self.drawn_at.get(start)?))
+    }
+
+    /// The area enclosed by the line, split into convex parts, given the line's polyline collider.
+    pub fn enclosed_area(&self, collider: &Collider) -> Option<Collider> {
+        let polyline = collider.shape().as_polyline()?;
+        Some(Collider::convex_decomposition(
+            self.line.clone(),
+            polyline.indices().to_vec(),
+        ))
+    }
+
+    /// The total length of the line in world units.
+    pub fn length(&self) -> f32 {
+        self.line
+            .iter()
+            .zip(self.line.iter().skip(1))
+            .map(length)
+            .sum()
+    }
+
+    /// How much more can be drawn before the oldest points are dropped, if the line has a maximum.
+    pub fn remaining_length(&self) -> Option<f32> {
+        self.max_line_length
+            .map(|max| (max as f32 - self.length()).max(0.))
+    }
+}
+
+/// The energy the styler spends drawing when [`LineEnergyDrain`] is enabled.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct StylerEnergy {
+    pub current: f32,
+    pub max: f32,
+}
+
+impl StylerEnergy {
+    pub fn new(max: f32) -> Self {
+        Self { current: max, max }
+    }
+
+    pub fn drain(&mut self, amount: f32) {
+        self.current = (self.current - amount).max(0.);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.current <= 0.
+    }
+}
+
+/// How much styler energy is spent per world unit of line drawn.
+///
+/// Drawing is free when this is `None`.
+#[derive(Resource, Reflect, Debug, Default)]
+#[reflect(Resource)]
+pub struct LineEnergyDrain(pub Option<f32>);
+
+/// The sprites for every [`StylerSkin`], and the one loaded from [`CaptureConfig::start_sprite`].
 #[derive(Resource, Reflect, Debug, Default)]
 #[reflect(Resource)]
-struct Assets {
-    styler: Handle<Image>,
-    styler_start: Handle<Image>,
+pub struct StylerSprites {
+    pub stylers: HashMap<StylerSkin, Handle<Image>>,
+    pub styler_start: Handle<Image>,
 }
 
 impl Default for CaptureLine {
     fn default() -> Self {
+        let (start_color, end_color) = LineColorScheme::Sky.colors();
         Self {
             line: vec![],
             width: 10.0,
-            start_color: Some(Color::linear_rgb(0.168_627_46, 0.211_764_71, 0.529_411_8)),
-            end_color: Some(Color::linear_rgb(0.411_764_7, 0.478_431_37, 0.980_392_16)),
+            start_color: Some(start_color),
+            end_color: Some(end_color),
             max_line_length: Some(500),
+            drawn_length: 0.,
+            drawn_at: vec![],
         }
     }
 }
 
-fn take_damage(capture_line: Single<&mut Health>, mut damage_event: EventReader<TakeDamage>) {
-    let mut capture_line = capture_line.into_inner();
-    for damage in damage_event.read() {
-        capture_line.0 -= damage.0;
-    }
+fn take_damage(damage: Trigger<TakeDamage>, mut health: Single<&mut Health>) {
+    health.0 = health.0.saturating_sub(damage.amount);
 }
 
-fn detect_complete(line: Single<&CaptureLine>, mut complete: EventWriter<CaptureLineConnected>) {
-    if line.line.is_empty() {
-        return;
+fn detect_complete(
+    mut commands: Commands,
+    line: Single<(&CaptureLine, &DespawnWith)>,
+    mut complete: EventWriter<CaptureLineConnected>,
+) {
+    let (line, session) = line.into_inner();
+    let intersections = line.intersections();
+    for intersection in &intersections {
+        complete.write(CaptureLineConnected {
+            session: session.0,
+            cull_to: (intersection.segment, intersection.points),
+        });
     }
 
-    let points = line.line.iter().zip(line.line[1..].iter());
-    for (i, first) in points.clone().enumerate() {
-        for second in points.clone().skip(i) {
-            if second.0 == first.1 {
-                continue;
-            }
-
-            let second_w1 = (
-                &(second.0 + (line.width / 2.)),
-                &(second.1 + (line.width / 2.)),
-            );
-            let second_w2 = (
-                &(second.0 - (line.width / 2.)),
-                &(second.1 - (line.width / 2.)),
-            );
-
-            let first_w1 = (
-                &(first.0 + (line.width / 2.)),
-                &(first.1 + (line.width / 2.)),
-            );
-            let first_w2 = (
-                &(first.0 - (line.width / 2.)),
-                &(first.1 - (line.width / 2.)),
-            );
-
-            if intersects(second, first).is_some()
-                || intersects(second_w1, first_w1).is_some()
-                || intersects(second_w2, first_w2).is_some()
-            {
-                complete.write(CaptureLineConnected {
-                    cull_to: (i, (*first.0, *first.1)),
-                });
-            }
-        }
+    // Every intersection found in a frame belongs to the same loop.
+    if !intersections.is_empty() {
+        set_session_state(&mut commands, session.0, SessionState::LoopClosed);
     }
 }
 
+#[allow(clippy::type_complexity)]
 fn increase_capture_progress(
     mut commands: Commands,
-    capture_line: Single<(&CaptureLine, &Collider)>,
-    creatures: Query<(Entity, &mut CaptureProgress, &Transform), Without<Captured>>,
+    mut connected: EventReader<CaptureLineConnected>,
+    capture_line: Single<(&CaptureLine, &Collider, &DespawnWith)>,
+    creatures: Query<
+        (
+            Entity,
+            &mut CaptureProgress,
+            &Transform,
+            Option<&Collider>,
+            Option<&CreatureType>,
+        ),
+        Without<Captured>,
+    >,
+    config: Res<CaptureConfig>,
+    styler: Option<Single<&Styler>>,
+    time: Res<Time>,
 ) {
-    let (line, our_collider) = capture_line.into_inner();
-    for (entity, mut progress, creature_location) in creatures {
-        let our_collider = our_collider.shape().as_polyline().unwrap();
-        let polygon =
-            Collider::convex_decomposition(line.line.clone(), our_collider.indices().to_vec());
-
-        if polygon.contains_point(
-            Vec2::ZERO,
-            Rotation::IDENTITY,
-            creature_location.translation.xy(),
-        ) {
-            progress.0 += 1;
-            commands.trigger(CaptureProgressChanged(entity));
-        }
-    }
-}
-
-fn adjust_linewidth(mut config_store: ResMut<GizmoConfigStore>, lines: Single<&CaptureLine>) {
-    let (config, _) = config_store.config_mut::<DefaultGizmoConfigGroup>();
-    config.line.joints = GizmoLineJoint::Round(1);
-    config.line.width = lines.width;
-    config.render_layers = RenderLayers::layer(1);
-}
+    let styler = styler_or_base(styler, &config);
+    let (line, our_collider, session) = capture_line.into_inner();
+    let Some(polygon) = line.enclosed_area(our_collider) else {
+        return;
+    };
 
-fn connect_points(lines: Single<&CaptureLine>, mut gizmos: Gizmos) {
-    if !lines.line.is_empty() {
-        if lines.start_color.is_none() || lines.end_color.is_none() {
-            let color = match (lines.start_color, lines.end_color) {
-                (Some(c), None) => c,
-                (None, Some(c)) => c,
-                _ => Color::WHITE,
-            };
-            gizmos.linestrip_2d(lines.line.clone(), color);
-        } else {
-            let start_color = lines.start_color.unwrap().to_linear();
-            let end_color = lines.end_color.unwrap().to_linear();
-            let lines_count = lines.line.len() as f32;
-            let lines = lines.line.clone().into_iter().enumerate().map(|(i, v)| {
-                (
-                    v,
-                    Color::LinearRgba(start_color.lerp(end_color, i as f32 / lines_count)),
-                )
+    let segments = connected.read().map(|connected| connected.cull_to.0);
+    let Some((points, started)) = line.closed_loop(segments) else {
+        return;
+    };
+    let shape = LoopShape::of(points, time.elapsed().saturating_sub(started));
+
+    for (entity, mut progress, creature_location, collider, creature_type) in creatures {
+        if config
+            .containment
+            .contains(&polygon, creature_location.translation.xy(), collider)
+        {
+            let amount = line_power(&shape, &styler, creature_type.copied());
+            progress.add(amount, time.elapsed());
+            commands.trigger(CaptureProgressChanged {
+                session: Some(session.0),
+                creature: entity,
+                change: amount as i32,
             });
-            gizmos.linestrip_gradient_2d(lines);
         }
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn add_points_to_capture_line(
-    mut ev_mouse: EventReader<CursorMoved>,
-    camera: Single<(&Camera, &GlobalTransform), Without<GizmoCamera>>,
-    lines: Single<(Entity, &mut CaptureLine)>,
+    mut moved: EventReader<CapturePointMoved>,
+    lines: Single<(Entity, &mut CaptureLine, &DespawnWith)>,
+    sessions: Query<&CaptureSession>,
+    energy: Single<&mut StylerEnergy>,
+    drain: Res<LineEnergyDrain>,
+    time: Res<Time>,
     mut commands: Commands,
 ) {
-    let (camera, transform) = camera.into_inner();
+    let mut energy = energy.into_inner();
+
+    let (e, mut line, session) = lines.into_inner();
+    let session = session.0;
+    if !moved.is_empty()
+        && sessions.get(session).is_ok_and(|session| {
+            matches!(
+                session.state,
+                SessionState::Started | SessionState::LoopClosed
+            )
+        })
+    {
+        set_session_state(&mut commands, session, SessionState::Drawing);
+    }
 
-    let (e, mut line) = lines.into_inner();
-    for mouse in ev_mouse.read() {
-        let line_pos = camera
-            .viewport_to_world_2d(transform, mouse.position)
-            .expect("Unable to get world coordinates from viewport!");
+    for CapturePointMoved(line_pos) in moved.read() {
+        let line_pos = *line_pos;
+
+        let segment_length = line.line.last().map_or(0., |last| last.distance(line_pos));
+
+        if let Some(per_unit) = drain.0 {
+            energy.drain(segment_length * per_unit);
+            if energy.is_empty() {
+                set_session_state(
+                    &mut commands,
+                    session,
+                    SessionState::Ended {
+                        reason: EndReason::EnergyDepleted,
+                    },
+                );
+                break;
+            }
+        }
 
         if let Some(line_max) = line.max_line_length {
             let line_max = line_max as f32;
-            if line.line.len() >= 2 {
-                let mut length: f32 = line
-                    .line
-                    .iter()
-                    .zip(line.line[1..].iter())
-                    .map(length)
-                    .sum();
-                while length > line_max {
-                    line.line.remove(0);
-                    length = line
-                        .line
-                        .iter()
-                        .zip(line.line[1..].iter())
-                        .map(math::length)
-                        .sum();
-                }
+            while line.line.len() >= 2 && line.length() > line_max {
+                line.line.remove(0);
+                line.drawn_at.remove(0);
             }
         }
 
         line.line.push(line_pos);
+        line.drawn_at.push(time.elapsed());
+        line.drawn_length += segment_length;
 
         if line.line.len() >= 2 {
             commands
@@ -357,6 +689,25 @@ fn add_points_to_capture_line(
     }
 }
 
+fn restart_capture_line(
+    mut commands: Commands,
+    mut restarted: EventReader<CapturePointRestarted>,
+    line: Single<(Entity, &mut CaptureLine)>,
+) {
+    restarted.clear();
+
+    let (e, mut line) = line.into_inner();
+    line.line.clear();
+    line.drawn_at.clear();
+    commands.entity(e).remove::<Collider>();
+}
+
+fn recover_energy(energy: Single<&mut StylerEnergy>, time: Res<Time>) {
+    let mut energy = energy.into_inner();
+    energy.current =
+        (energy.current + ENERGY_RECOVERY_PER_SECOND * time.delta_secs()).min(energy.max);
+}
+
 fn truncate_capture_line_to_intersection(
     lines: Single<(Entity, &mut CaptureLine)>,
     mut commands: Commands,
@@ -378,6 +729,7 @@ fn truncate_capture_line_to_intersection(
         let (point_a1, point_a2) = points[complete.cull_to.0];
         if point_a1.1 == &complete.cull_to.1 .0 && point_a2.1 == &complete.cull_to.1 .1 {
             lines.line.truncate(point_a1.0);
+            lines.drawn_at.truncate(point_a1.0);
             if lines.line.len() >= 2 {
                 commands
                     .entity(e)
@@ -418,70 +770,123 @@ fn trail_line(
     }
 }
 
-fn destroy_line(line: Single<Entity, With<CaptureLineStart>>, mut commands: Commands) {
-    let e = line.into_inner();
-    commands.entity(e).insert(Despawn);
+/// The session that is still going, if there is one.
+fn active_session(sessions: &Query<(Entity, &CaptureSession)>) -> Option<Entity> {
+    sessions
+        .iter()
+        .find(|(_, session)| !matches!(session.state, SessionState::Ended { .. }))
+        .map(|(entity, _)| entity)
 }
 
-fn reset_capture_progress(creature_progress: Query<&mut CaptureProgress, Without<Captured>>) {
+fn destroy_line(changed: Trigger<CaptureSessionChanged>, mut commands: Commands) {
+    if changed.ended().is_some() {
+        commands.entity(changed.session).insert(Despawn);
+    }
+}
+
+/// Breaking the line or leaving the window loses all progress, unlike lifting it.
+fn reset_capture_progress(
+    changed: Trigger<CaptureSessionChanged>,
+    creature_progress: Query<&mut CaptureProgress, Without<Captured>>,
+) {
+    if !matches!(
+        changed.ended(),
+        Some(EndReason::Hit | EndReason::LeftWindow)
+    ) {
+        return;
+    }
+
     for mut progress in creature_progress {
-        progress.0 = 0;
+        progress.reset();
+    }
+}
+
+fn end_cancelled_session(mut commands: Commands, sessions: Query<(Entity, &CaptureSession)>) {
+    if let Some(session) = active_session(&sessions) {
+        set_session_state(
+            &mut commands,
+            session,
+            SessionState::Ended {
+                reason: EndReason::LeftWindow,
+            },
+        );
     }
 }
 
 fn emit_capture_events(
     mut commands: Commands,
     creatures: Query<(Entity, &CaptureProgress, &CaptureRequirements), Without<Captured>>,
-    mut capture_event: EventWriter<CaptureSuccess>,
-    mut failed_capture_event: EventWriter<CaptureFailed>,
+    sessions: Query<(Entity, &CaptureSession)>,
 ) {
+    // Lifting after the session has already ended, such as after the line was hit, does nothing.
+    let Some(session) = active_session(&sessions) else {
+        return;
+    };
+
+    let mut captured = vec![];
+    let mut failed = vec![];
     for (entity, progress, requirements) in creatures.iter() {
-        if progress.0 >= requirements.0 {
-            capture_event.write(CaptureSuccess {
-                captured: entity,
-                overshot_by: (progress.0 - requirements.0) as usize,
+        if progress.progress >= requirements.0 {
+            captured.push(CapturedCreature {
+                creature: entity,
+                overshot_by: (progress.progress - requirements.0) as usize,
             });
             commands.entity(entity).insert(Captured);
-            commands.trigger(CaptureSuccess {
-                captured: entity,
-                overshot_by: (progress.0 - requirements.0) as usize,
-            });
         } else {
-            failed_capture_event.write(CaptureFailed(entity));
-            commands.trigger(CaptureFailed(entity));
+            failed.push(entity);
         }
     }
+
+    let reason = if captured.is_empty() {
+        EndReason::Lifted { failed }
+    } else {
+        EndReason::Success { captured, failed }
+    };
+
+    set_session_state(&mut commands, session, SessionState::Ended { reason });
 }
 
-fn player_start_capture(
+fn start_capture(
     mut commands: Commands,
-    mut event_writer: EventWriter<CapturePointPressed>,
-    window: Single<&Window, With<PrimaryWindow>>,
-    camera: Single<(&Camera, &GlobalTransform), Without<GizmoCamera>>,
-    assets: Res<Assets>,
+    mut pressed: EventReader<CapturePointPressed>,
+    sprites: Res<StylerSprites>,
+    config: Res<CaptureConfig>,
+    settings: Res<Settings>,
+    styler: Option<Single<&Styler>>,
+    time: Res<Time>,
 ) {
-    event_writer.write(CapturePointPressed);
-
-    let window = window.into_inner();
-    let (camera, camera_transform) = camera.into_inner();
-    let current_point = camera
-        .viewport_to_world_2d(camera_transform, window.cursor_position().unwrap())
-        .expect("Unable to calculate cursor position!");
+    let styler = styler_or_base(styler, &config);
+    let Some(CapturePointPressed(current_point)) = pressed.read().last() else {
+        return;
+    };
+    let current_point = *current_point;
 
-    let mut sprite = Sprite::from_image(assets.styler_start.clone_weak());
+    let mut sprite = Sprite::from_image(sprites.styler_start.clone_weak());
     sprite.custom_size = Some(Vec2::new(9., 9.));
     let parent = commands
         .spawn((
+            Name::from("Capture Session"),
+            CaptureSession {
+                state: SessionState::Started,
+            },
             CaptureLineStart,
             sprite,
             Transform::from_translation(current_point.extend(0.)),
         ))
         .id();
+    set_session_state(&mut commands, parent, SessionState::Started);
 
+    let (start_color, end_color) = config
+        .line_colors
+        .unwrap_or_else(|| settings.line_gradient());
     commands.spawn((
         CaptureLine {
             line: vec![current_point],
-            width: 12.,
+            width: styler.width,
+            start_color: Some(start_color),
+            end_color: Some(end_color),
+            max_line_length: styler.max_line_length,
+            drawn_at: vec![time.elapsed()],
             ..default()
         },
         DespawnWith(parent),
@@ -489,12 +894,14 @@ fn player_start_capture(
 
     commands.spawn((
         CaptureStyler,
-        Sprite::from_image(assets.styler.clone_weak()),
+        Sprite::from_image(
+            sprites
+                .stylers
+                .get(&styler.skin)
+                .map(Handle::clone_weak)
+                .unwrap_or_default(),
+        ),
         Transform::from_translation(current_point.extend(-1.)),
         DespawnWith(parent),
     ));
 }
-
-fn player_stop_capture(mut event_writer: EventWriter<CapturePointLifted>) {
-    event_writer.write(CapturePointLifted);
-}
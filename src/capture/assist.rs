@@ -0,0 +1,203 @@
+use crate::capture::record::replaying;
+use crate::capture::{CaptureLine, CaptureProgressChanged, CaptureStyler, Captured};
+use crate::creature::roster::Roster;
+use crate::creature::{CaptureProgress, Creature};
+use crate::movement::Speed;
+use crate::pause::{paused, SlowMotion};
+use crate::settings::{binding_just_pressed, rebinding_key};
+use crate::DespawnWith;
+use bevy::input::InputSystem;
+use bevy::prelude::*;
+use std::time::Duration;
+
+pub struct AssistPlugin;
+impl Plugin for AssistPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<AssistPressed>()
+            .register_type::<Assist>()
+            .register_type::<AssistCooldown>()
+            .register_type::<Slowed>()
+            .register_type::<LineShield>()
+            .init_resource::<AssistCooldown>()
+            .add_systems(Update, (tick_assist_cooldown, recover_from_slow))
+            .add_systems(
+                PreUpdate,
+                player_press_assist.after(InputSystem).run_if(
+                    binding_just_pressed(|bindings| bindings.assist)
+                        .and(any_with_component::<CaptureLine>)
+                        .and(not(replaying))
+                        .and(not(paused))
+                        .and(not(rebinding_key)),
+                ),
+            )
+            .add_systems(
+                Update,
+                use_assist.run_if(on_event::<AssistPressed>.and(any_with_component::<CaptureLine>)),
+            );
+    }
+}
+
+/// The assist a creature provides to the player while it is their partner.
+#[derive(Component, Reflect, Debug, Clone, PartialEq)]
+#[reflect(Component)]
+pub enum Assist {
+    /// Adds `amount` progress to every creature within `radius` of the styler.
+    ProgressBurst { radius: f32, amount: u32 },
+
+    /// Multiplies the speed of every creature by `factor` for `seconds`, stopping them entirely
+    /// if it is zero or less.
+    Slow { factor: f32, seconds: f32 },
+
+    /// Protects the capture line from the next hit it takes.
+    Shield,
+
+    /// Scales time by `scale` for `seconds` of real time, while the capture line keeps up with
+    /// the cursor.
+    SlowMotion { scale: f32, seconds: f32 },
+}
+
+impl Assist {
+    /// How long the player must wait after using this assist before using another.
+    pub fn cooldown(&self) -> Duration {
+        match self {
+            Assist::ProgressBurst { .. } => Duration::from_secs(10),
+            Assist::Slow { .. } => Duration::from_secs(8),
+            Assist::Shield => Duration::from_secs(6),
+            Assist::SlowMotion { .. } => Duration::from_secs(12),
+        }
+    }
+}
+
+/// Represents when the player has asked for their partner's assist, once it has cooled down.
+#[derive(Event, Debug)]
+pub struct AssistPressed(pub Assist);
+
+/// Represents when the player has used their partner's assist.
+#[derive(Event, Debug)]
+pub struct AssistUsed(pub Assist);
+
+#[derive(Resource, Reflect, Debug, Deref, DerefMut)]
+#[reflect(Resource)]
+pub struct AssistCooldown(Timer);
+
+impl Default for AssistCooldown {
+    fn default() -> Self {
+        let mut timer = Timer::new(Duration::ZERO, TimerMode::Once);
+        timer.tick(Duration::ZERO);
+        Self(timer)
+    }
+}
+
+/// A creature that has been slowed down by an [`Assist::Slow`].
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct Slowed {
+    timer: Timer,
+
+    /// The creature's speed before it was slowed, which it goes back to afterwards.
+    original_speed: f32,
+}
+
+/// Stops the next hit on the capture line from breaking it.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub(super) struct LineShield;
+
+fn tick_assist_cooldown(mut cooldown: ResMut<AssistCooldown>, time: Res<Time>) {
+    cooldown.tick(time.delta());
+}
+
+fn player_press_assist(
+    mut assist_pressed: EventWriter<AssistPressed>,
+    cooldown: Res<AssistCooldown>,
+    roster: Option<Res<Roster>>,
+) {
+    // Without a roster, as when capturing is used on its own, there's no partner to assist.
+    let Some(assist) = roster
+        .as_deref()
+        .and_then(Roster::partner)
+        .and_then(|partner| partner.assist.clone())
+    else {
+        return;
+    };
+
+    if cooldown.finished() {
+        assist_pressed.write(AssistPressed(assist));
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn use_assist(
+    mut commands: Commands,
+    mut assist_pressed: EventReader<AssistPressed>,
+    mut cooldown: ResMut<AssistCooldown>,
+    time: Res<Time>,
+    capture_line: Single<(Entity, &DespawnWith), With<CaptureLine>>,
+    styler: Single<&Transform, With<CaptureStyler>>,
+    mut creatures: Query<
+        (
+            Entity,
+            &mut CaptureProgress,
+            &Transform,
+            Option<&mut Speed>,
+            Has<Slowed>,
+        ),
+        (With<Creature>, Without<Captured>),
+    >,
+) {
+    let (capture_line, session) = capture_line.into_inner();
+    for AssistPressed(assist) in assist_pressed.read() {
+        match *assist {
+            Assist::ProgressBurst { radius, amount } => {
+                let styler = styler.translation.xy();
+                for (entity, mut progress, transform, _, _) in creatures.iter_mut() {
+                    if transform.translation.xy().distance(styler) <= radius {
+                        progress.add(amount, time.elapsed());
+                        commands.trigger(CaptureProgressChanged {
+                            session: Some(session.0),
+                            creature: entity,
+                            change: amount as i32,
+                        });
+                    }
+                }
+            }
+            Assist::Slow { factor, seconds } => {
+                for (entity, _, _, speed, slowed) in creatures.iter_mut() {
+                    let (Some(mut speed), false) = (speed, slowed) else {
+                        continue;
+                    };
+
+                    commands.entity(entity).insert(Slowed {
+                        timer: Timer::from_seconds(seconds, TimerMode::Once),
+                        original_speed: speed.0,
+                    });
+                    speed.0 *= factor.max(0.);
+                }
+            }
+            Assist::Shield => {
+                commands.entity(capture_line).insert(LineShield);
+            }
+            Assist::SlowMotion { scale, seconds } => {
+                commands.insert_resource(SlowMotion::new(scale, seconds));
+            }
+        }
+
+        cooldown.set_duration(assist.cooldown());
+        cooldown.reset();
+        commands.trigger(AssistUsed(assist.clone()));
+    }
+}
+
+fn recover_from_slow(
+    mut commands: Commands,
+    time: Res<Time>,
+    slowed: Query<(Entity, &mut Slowed, &mut Speed)>,
+) {
+    for (entity, mut slowed, mut speed) in slowed {
+        slowed.timer.tick(time.delta());
+        if slowed.timer.finished() {
+            speed.0 = slowed.original_speed;
+            commands.entity(entity).remove::<Slowed>();
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use avian2d::position::Rotation;
+use avian2d::prelude::{Collider, SimpleCollider};
+use bevy::prelude::*;
+
+/// How capture lines look and behave, set through [`CaptureCorePlugin`].
+///
+/// [`CaptureCorePlugin`]: crate::capture::CaptureCorePlugin
+#[derive(Resource, Debug, Clone)]
+pub struct CaptureConfig {
+    /// The width of the line drawn by a styler without upgrades.
+    pub line_width: f32,
+
+    /// The colours the line fades between, which follow the player's settings if not set.
+    pub line_colors: Option<(Color, Color)>,
+
+    /// How long the line can get before its oldest points are dropped, in world units, for a
+    /// styler without upgrades.
+    pub max_line_length: Option<usize>,
+    pub containment: Containment,
+
+    /// Asset path of the standard sprite that follows the end of the line.
+    pub styler_sprite: String,
+
+    /// Asset path of the sprite that marks the start of the line.
+    pub start_sprite: String,
+}
+
+impl Default for CaptureConfig {
+    fn default() -> Self {
+        Self {
+            line_width: 12.,
+            line_colors: None,
+            max_line_length: Some(500),
+            containment: Containment::Position,
+            styler_sprite: "Capture-Styler.png".into(),
+            start_sprite: "captureline-start2.png".into(),
+        }
+    }
+}
+
+/// What counts as a creature being inside a loop.
+#[derive(Debug, Clone, Copy, Default)]
+pub enum Containment {
+    /// The creature's position is inside the loop.
+    #[default]
+    Position,
+
+    /// All of the creature's collider bounds are inside the loop. Creatures without a collider
+    /// fall back to [`Containment::Position`].
+    WholeCollider,
+
+    /// Decided by a function given the area inside the loop, and the creature's position and
+    /// collider.
+    Custom(fn(&Collider, Vec2, Option<&Collider>) -> bool),
+}
+
+impl Containment {
+    /// Whether a creature at `position` is inside `area`, the shape enclosed by a loop.
+    pub fn contains(&self, area: &Collider, position: Vec2, collider: Option<&Collider>) -> bool {
+        let inside = |point| area.contains_point(Vec2::ZERO, Rotation::IDENTITY, point);
+        match (self, collider) {
+            (Containment::WholeCollider, Some(collider)) => {
+                let bounds = collider.aabb(position, Rotation::IDENTITY);
+                [
+                    bounds.min,
+                    Vec2::new(bounds.min.x, bounds.max.y),
+                    bounds.max,
+                    Vec2::new(bounds.max.x, bounds.min.y),
+                ]
+                .into_iter()
+                .all(inside)
+            }
+            (Containment::Custom(contains), _) => contains(area, position, collider),
+            _ => inside(position),
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use crate::capture::session::CaptureSession;
+use crate::capture::{active_session, CaptureProgressChanged, Captured};
+use crate::creature::{CaptureDecay, CaptureProgress};
+use crate::settings::Settings;
+use bevy::prelude::*;
+
+/// How long a creature shakes for when it breaks free.
+const BREAK_FREE_SECONDS: f32 = 0.6;
+
+pub struct DecayPlugin;
+impl Plugin for DecayPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<BreakingFree>()
+            .add_systems(Update, (decay_capture_progress, animate_breaking_free))
+            .add_observer(start_breaking_free);
+    }
+}
+
+/// Represents when a creature that had been looped has had all of its progress drain away.
+#[derive(Event, Debug)]
+pub struct CreatureBrokeFree(pub Entity);
+
+#[derive(Component, Reflect, Debug, Deref, DerefMut)]
+#[reflect(Component)]
+struct BreakingFree(Timer);
+
+fn decay_capture_progress(
+    mut commands: Commands,
+    time: Res<Time>,
+    creatures: Query<(Entity, &mut CaptureProgress, &mut CaptureDecay), Without<Captured>>,
+    sessions: Query<(Entity, &CaptureSession)>,
+) {
+    let session = active_session(&sessions);
+    let now = time.elapsed();
+    for (entity, mut progress, mut decay) in creatures {
+        match decay.starts_in(&progress, now) {
+            Some(remaining) if remaining.is_zero() => {}
+            _ => {
+                decay.timer.reset();
+                continue;
+            }
+        }
+
+        decay.timer.tick(time.delta());
+        let drained = decay.timer.times_finished_this_tick();
+        if drained == 0 {
+            continue;
+        }
+
+        progress.progress = progress.progress.saturating_sub(drained);
+        commands.trigger(CaptureProgressChanged {
+            session,
+            creature: entity,
+            change: -(drained as i32),
+        });
+
+        if progress.progress == 0 {
+            progress.reset();
+            commands.trigger(CreatureBrokeFree(entity));
+        }
+    }
+}
+
+fn start_breaking_free(broke_free: Trigger<CreatureBrokeFree>, mut commands: Commands) {
+    commands
+        .entity(broke_free.0)
+        .insert(BreakingFree(Timer::from_seconds(
+            BREAK_FREE_SECONDS,
+            TimerMode::Once,
+        )));
+}
+
+fn animate_breaking_free(
+    mut commands: Commands,
+    time: Res<Time>,
+    settings: Res<Settings>,
+    creatures: Query<(Entity, &mut BreakingFree, &mut Transform)>,
+) {
+    for (entity, mut breaking_free, mut transform) in creatures {
+        breaking_free.tick(time.delta());
+
+        if breaking_free.finished() || settings.reduced_motion {
+            transform.scale = Vec3::ONE;
+            commands.entity(entity).remove::<BreakingFree>();
+        } else {
+            let wobble = (breaking_free.elapsed_secs() * 40.).sin() * 0.15;
+            transform.scale = Vec3::new(1. + wobble, 1. - wobble, 1.);
+        }
+    }
+}
@@ -0,0 +1,168 @@
+use crate::capture::math::triangulate;
+use crate::capture::session::{CaptureSessionChanged, SessionState};
+use crate::capture::{CaptureLine, CaptureStyler};
+use crate::settings::Settings;
+use crate::{Despawn, DespawnWith};
+use bevy::asset::RenderAssetUsages;
+use bevy::prelude::*;
+use bevy::render::mesh::{Indices, PrimitiveTopology};
+use std::f32::consts::PI;
+
+/// How long the loop flash and its sparkles last.
+const FLASH_SECONDS: f32 = 0.4;
+
+const FLASH_ALPHA: f32 = 0.4;
+
+/// How many points of the loop are skipped between each sparkle.
+const SPARKLE_SPACING: usize = 3;
+
+const SPARKLE_SPEED: f32 = 60.;
+
+const STYLER_PULSE_SECONDS: f32 = 0.25;
+
+pub struct LoopEffectsPlugin;
+impl Plugin for LoopEffectsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<LoopFlash>()
+            .register_type::<Sparkle>()
+            .register_type::<StylerPulse>()
+            .add_observer(spawn_loop_effects)
+            .add_systems(Update, (fade_loop_flash, move_sparkles, pulse_styler));
+    }
+}
+
+/// The filled shape of a loop that was just closed. Its sparkles are despawned along with it.
+#[derive(Component, Reflect, Debug, Deref, DerefMut)]
+#[reflect(Component)]
+struct LoopFlash(Timer);
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct Sparkle {
+    velocity: Vec2,
+    timer: Timer,
+}
+
+#[derive(Component, Reflect, Debug, Deref, DerefMut)]
+#[reflect(Component)]
+struct StylerPulse(Timer);
+
+fn spawn_loop_effects(
+    changed: Trigger<CaptureSessionChanged>,
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    line: Single<&CaptureLine>,
+    styler: Single<Entity, With<CaptureStyler>>,
+    settings: Res<Settings>,
+) {
+    if changed.state != SessionState::LoopClosed {
+        return;
+    }
+
+    // The line isn't truncated to the loop until the end of the frame, so it can be found the same
+    // way the loop's capture progress was.
+    let segments = line
+        .intersections()
+        .into_iter()
+        .map(|intersection| intersection.segment);
+    let Some((loop_points, _)) = line.closed_loop(segments) else {
+        return;
+    };
+    if loop_points.len() < 3 {
+        return;
+    }
+
+    let color = line.end_color.or(line.start_color).unwrap_or(Color::WHITE);
+    let mesh = Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::default(),
+    )
+    .with_inserted_attribute(
+        Mesh::ATTRIBUTE_POSITION,
+        loop_points
+            .iter()
+            .map(|point| point.extend(0.).to_array())
+            .collect::<Vec<_>>(),
+    )
+    .with_inserted_indices(Indices::U32(triangulate(loop_points)));
+
+    let flash = commands
+        .spawn((
+            Name::from("Loop Flash"),
+            LoopFlash(Timer::from_seconds(FLASH_SECONDS, TimerMode::Once)),
+            Mesh2d(meshes.add(mesh)),
+            MeshMaterial2d(materials.add(color.with_alpha(FLASH_ALPHA))),
+            Transform::from_xyz(0., 0., -0.5),
+        ))
+        .id();
+
+    if settings.reduced_motion {
+        return;
+    }
+
+    let center = loop_points.iter().sum::<Vec2>() / loop_points.len() as f32;
+    for point in loop_points.iter().step_by(SPARKLE_SPACING) {
+        commands.spawn((
+            Sparkle {
+                velocity: (*point - center).normalize_or_zero() * SPARKLE_SPEED,
+                timer: Timer::from_seconds(FLASH_SECONDS, TimerMode::Once),
+            },
+            Sprite::from_color(Color::WHITE, Vec2::splat(2.)),
+            Transform::from_translation(point.extend(1.)),
+            DespawnWith(flash),
+        ));
+    }
+
+    commands
+        .entity(styler.into_inner())
+        .insert(StylerPulse(Timer::from_seconds(
+            STYLER_PULSE_SECONDS,
+            TimerMode::Once,
+        )));
+}
+
+fn fade_loop_flash(
+    mut commands: Commands,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    time: Res<Time>,
+    flashes: Query<(Entity, &mut LoopFlash, &MeshMaterial2d<ColorMaterial>), Without<Despawn>>,
+) {
+    for (entity, mut flash, material) in flashes {
+        flash.tick(time.delta());
+
+        if let Some(material) = materials.get_mut(&material.0) {
+            material
+                .color
+                .set_alpha(FLASH_ALPHA * (1. - flash.fraction()));
+        }
+
+        if flash.finished() {
+            commands.entity(entity).insert(Despawn);
+        }
+    }
+}
+
+fn move_sparkles(time: Res<Time>, sparkles: Query<(&mut Sparkle, &mut Transform, &mut Sprite)>) {
+    for (mut sparkle, mut transform, mut sprite) in sparkles {
+        sparkle.timer.tick(time.delta());
+        transform.translation += (sparkle.velocity * time.delta_secs()).extend(0.);
+        sprite.color.set_alpha(1. - sparkle.timer.fraction());
+    }
+}
+
+fn pulse_styler(
+    mut commands: Commands,
+    time: Res<Time>,
+    stylers: Query<(Entity, &mut StylerPulse, &mut Transform)>,
+) {
+    for (entity, mut pulse, mut transform) in stylers {
+        pulse.tick(time.delta());
+        transform.scale = Vec3::splat(1. + (pulse.fraction() * PI).sin() * 0.3);
+
+        if pulse.finished() {
+            transform.scale = Vec3::ONE;
+            commands.entity(entity).remove::<StylerPulse>();
+        }
+    }
+}
@@ -0,0 +1,160 @@
+use crate::camera::GizmoCamera;
+use crate::capture::record::{replaying, Replay};
+use crate::capture::{
+    CancelReason, CaptureCancelled, CaptureLine, CapturePointLifted, CapturePointMoved,
+    CapturePointPressed, CapturePointRestarted,
+};
+use crate::pause::{paused, GameResumed};
+use crate::settings::{CursorLeavePolicy, Settings};
+use bevy::input::InputSystem;
+use bevy::prelude::*;
+use bevy::window::{CursorEntered, PrimaryWindow};
+use std::time::Duration;
+
+/// Turns mouse input into the world-space events that drive a capture.
+pub struct CaptureInputPlugin;
+impl Plugin for CaptureInputPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(stop_capture_on_resume).add_systems(
+            PreUpdate,
+            (
+                player_start_capture.run_if(capture_just_pressed),
+                cursor_left_capture
+                    .run_if(on_event::<CursorLeft>.and(any_with_component::<CaptureLine>)),
+                cursor_returned.run_if(on_event::<CursorEntered>),
+                wait_for_cursor.run_if(resource_exists::<CursorAway>),
+                player_move_capture.run_if(
+                    any_with_component::<CaptureLine>.and(not(resource_exists::<CursorAway>)),
+                ),
+                player_stop_capture.run_if(capture_just_released),
+            )
+                .chain()
+                .after(InputSystem)
+                .run_if(not(replaying).and(not(paused))),
+        );
+    }
+}
+
+/// The cursor left the window mid-capture with [`CursorLeavePolicy::Wait`], and the capture is
+/// cancelled if it isn't back before the timer finishes.
+#[derive(Resource, Debug, Deref, DerefMut)]
+struct CursorAway(Timer);
+
+fn capture_just_pressed(settings: Res<Settings>, input: Res<ButtonInput<MouseButton>>) -> bool {
+    input.any_just_pressed(settings.bindings.capture.iter().copied())
+}
+
+/// Also used to end the capture if the button was let go while the game was paused.
+fn capture_pressed(settings: Res<Settings>, input: Res<ButtonInput<MouseButton>>) -> bool {
+    input.any_pressed(settings.bindings.capture.iter().copied())
+}
+
+fn capture_just_released(settings: Res<Settings>, input: Res<ButtonInput<MouseButton>>) -> bool {
+    input.any_just_released(settings.bindings.capture.iter().copied())
+}
+
+fn player_start_capture(
+    mut event_writer: EventWriter<CapturePointPressed>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    camera: Single<(&Camera, &GlobalTransform), Without<GizmoCamera>>,
+) {
+    let window = window.into_inner();
+    let (camera, camera_transform) = camera.into_inner();
+
+    // The button can be pressed while the cursor is outside the window, such as over its title bar.
+    let Some(current_point) = window
+        .cursor_position()
+        .and_then(|position| camera.viewport_to_world_2d(camera_transform, position).ok())
+    else {
+        return;
+    };
+
+    event_writer.write(CapturePointPressed(current_point));
+}
+
+fn player_move_capture(
+    mut ev_mouse: EventReader<CursorMoved>,
+    mut event_writer: EventWriter<CapturePointMoved>,
+    camera: Single<(&Camera, &GlobalTransform), Without<GizmoCamera>>,
+) {
+    let (camera, transform) = camera.into_inner();
+    for mouse in ev_mouse.read() {
+        // The cursor keeps moving while a button is held outside the window, so the styler is
+        // kept at the edge of the viewport.
+        let position = match camera.logical_viewport_rect() {
+            Some(viewport) => mouse.position.clamp(viewport.min, viewport.max),
+            None => mouse.position,
+        };
+        let Ok(line_pos) = camera.viewport_to_world_2d(transform, position) else {
+            continue;
+        };
+
+        event_writer.write(CapturePointMoved(line_pos));
+    }
+}
+
+fn cursor_left_capture(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    mut cancelled: EventWriter<CaptureCancelled>,
+) {
+    match settings.cursor_leave {
+        CursorLeavePolicy::Wait => commands.insert_resource(CursorAway(Timer::new(
+            Duration::from_millis(settings.cursor_grace_ms),
+            TimerMode::Once,
+        ))),
+        CursorLeavePolicy::Cancel => {
+            cancelled.write(CaptureCancelled(CancelReason::CursorLeft));
+        }
+        // Clamping happens as the cursor moves, and pausing is left to the pause menu.
+        CursorLeavePolicy::Clamp | CursorLeavePolicy::Pause => {}
+    }
+}
+
+/// Restarts the line where the cursor comes back, instead of joining it to where the cursor left.
+fn cursor_returned(
+    mut commands: Commands,
+    away: Option<Res<CursorAway>>,
+    mut restarted: EventWriter<CapturePointRestarted>,
+) {
+    if away.is_some() {
+        restarted.write(CapturePointRestarted);
+        commands.remove_resource::<CursorAway>();
+    }
+}
+
+fn wait_for_cursor(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut away: ResMut<CursorAway>,
+    mut cancelled: EventWriter<CaptureCancelled>,
+    capture_line: Query<(), With<CaptureLine>>,
+) {
+    if capture_line.is_empty() {
+        commands.remove_resource::<CursorAway>();
+        return;
+    }
+
+    away.tick(time.delta());
+    if away.finished() {
+        cancelled.write(CaptureCancelled(CancelReason::GracePeriodExpired));
+        commands.remove_resource::<CursorAway>();
+    }
+}
+
+fn player_stop_capture(mut event_writer: EventWriter<CapturePointLifted>) {
+    event_writer.write(CapturePointLifted);
+}
+
+fn stop_capture_on_resume(
+    _resumed: Trigger<GameResumed>,
+    mut event_writer: EventWriter<CapturePointLifted>,
+    settings: Res<Settings>,
+    input: Res<ButtonInput<MouseButton>>,
+    replay: Option<Res<Replay>>,
+    capture_line: Query<(), With<CaptureLine>>,
+) {
+    if !capture_line.is_empty() && !replaying(replay) && !capture_pressed(settings, input) {
+        event_writer.write(CapturePointLifted);
+    }
+}
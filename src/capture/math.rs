@@ -32,3 +32,64 @@ pub(super) fn length(segment: (&Vec2, &Vec2)) -> f32 {
 
     ((x2 - x1).squared() + (y2 - y1).squared()).sqrt()
 }
+
+/// Splits a simple polygon into triangles by ear clipping, returning indices into `polygon`.
+///
+/// Any part of the polygon that cannot be clipped (for instance, if it intersects itself) is left
+/// out.
+pub(super) fn triangulate(polygon: &[Vec2]) -> Vec<u32> {
+    let mut remaining = (0..polygon.len()).collect::<Vec<_>>();
+    let area: f32 = polygon
+        .iter()
+        .zip(polygon.iter().cycle().skip(1))
+        .map(|(a, b)| a.perp_dot(*b))
+        .sum();
+    if area < 0. {
+        remaining.reverse();
+    }
+
+    let mut triangles = vec![];
+    while remaining.len() > 3 {
+        let count = remaining.len();
+        let ear = (0..count).find(|&i| {
+            let (a, b, c) = (
+                remaining[(i + count - 1) % count],
+                remaining[i],
+                remaining[(i + 1) % count],
+            );
+            let (pa, pb, pc) = (polygon[a], polygon[b], polygon[c]);
+            if (pb - pa).perp_dot(pc - pb) <= 0. {
+                return false;
+            }
+
+            !remaining
+                .iter()
+                .filter(|&&p| p != a && p != b && p != c)
+                .any(|&p| in_triangle(polygon[p], (pa, pb, pc)))
+        });
+
+        let Some(i) = ear else {
+            break;
+        };
+
+        triangles.extend([
+            remaining[(i + count - 1) % count] as u32,
+            remaining[i] as u32,
+            remaining[(i + 1) % count] as u32,
+        ]);
+        remaining.remove(i);
+    }
+
+    if remaining.len() == 3 {
+        triangles.extend(remaining.iter().map(|&i| i as u32));
+    }
+
+    triangles
+}
+
+fn in_triangle(point: Vec2, (a, b, c): (Vec2, Vec2, Vec2)) -> bool {
+    let ab = (b - a).perp_dot(point - a);
+    let bc = (c - b).perp_dot(point - b);
+    let ca = (a - c).perp_dot(point - c);
+    ab >= 0. && bc >= 0. && ca >= 0.
+}
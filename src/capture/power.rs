@@ -0,0 +1,59 @@
+use crate::capture::math::length;
+use crate::capture::styler::Styler;
+use crate::creature::types::CreatureType;
+use bevy::prelude::*;
+use std::time::Duration;
+
+/// The radius of a loop that is neither tight nor lazy.
+const REFERENCE_RADIUS: f32 = 60.;
+
+/// How fast, in world units per second, a loop has to be drawn to be neither fast nor slow.
+const REFERENCE_SPEED: f32 = 400.;
+
+/// The most a loop's size or speed can multiply its power by, and one over the least.
+const MAX_QUALITY: f32 = 2.;
+
+/// The shape of a single closed loop, and how long it took to draw.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LoopShape {
+    pub area: f32,
+    pub perimeter: f32,
+    pub duration: Duration,
+}
+
+impl LoopShape {
+    /// Measures the loop made by joining the last point back to the first.
+    pub fn of(points: &[Vec2], duration: Duration) -> Self {
+        let closed = || points.iter().zip(points.iter().cycle().skip(1));
+        let area = closed().map(|(a, b)| a.perp_dot(*b)).sum::<f32>().abs() / 2.;
+        Self {
+            area,
+            perimeter: closed().map(length).sum(),
+            duration,
+        }
+    }
+
+    /// More than one for loops tighter than the reference loop, less than one for bigger ones.
+    pub fn tightness(&self) -> f32 {
+        let reference = std::f32::consts::PI * REFERENCE_RADIUS * REFERENCE_RADIUS;
+        (reference / self.area.max(1.))
+            .sqrt()
+            .clamp(1. / MAX_QUALITY, MAX_QUALITY)
+    }
+
+    /// More than one for loops drawn faster than the reference speed, less than one for slower.
+    pub fn speed(&self) -> f32 {
+        let speed = self.perimeter / self.duration.as_secs_f32().max(f32::EPSILON);
+        (speed / REFERENCE_SPEED).clamp(1. / MAX_QUALITY, MAX_QUALITY)
+    }
+}
+
+/// How much capture progress a loop gives a creature of `defender` type.
+///
+/// The styler's power is scaled by how tight and fast the loop was, and by the type matchup.
+/// Every loop around a creature gives at least one progress.
+pub fn line_power(shape: &LoopShape, styler: &Styler, defender: Option<CreatureType>) -> u32 {
+    let matchup = defender.map_or(1., |defender| styler.skin.element().matchup(defender));
+    let power = styler.power as f32 * shape.tightness() * shape.speed() * matchup;
+    (power.round() as u32).max(1)
+}
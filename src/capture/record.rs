@@ -0,0 +1,859 @@
+//! Recording capture sessions to a compact file, and replaying them back through the capture rules.
+//!
+//! Setting `BEVYMON_REPLAY` to the path of a recording replays it instead of reading the mouse,
+//! with the creatures that are already there set aside until it has finished.
+
+use crate::capture::assist::{Assist, AssistPressed};
+use crate::capture::styler::{Styler, StylerSkin};
+use crate::capture::{
+    start_capture, CancelReason, CaptureCancelled, CaptureLine, CapturePointLifted,
+    CapturePointMoved, CapturePointPressed, CapturePointRestarted, Captured,
+};
+use crate::creature::types::CreatureType;
+use crate::creature::{CaptureDecay, CaptureProgress, CaptureRequirements, Creature};
+use crate::save;
+use avian2d::prelude::Collider;
+use bevy::input::InputSystem;
+use bevy::prelude::*;
+use bevy::tasks::IoTaskPool;
+use bevy::time::{TimeSystem, TimeUpdateStrategy};
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const MAGIC: &[u8; 4] = b"BMRC";
+
+const VERSION: u8 = 4;
+
+/// The extension given to recordings written by the [`Recorder`].
+pub const RECORDING_EXTENSION: &str = "bmr";
+
+pub struct RecordPlugin;
+impl Plugin for RecordPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<ReplayedCreature>()
+            .register_type::<ReplayedStyler>()
+            .register_type::<SetAside>()
+            .init_resource::<Recorder>()
+            .add_observer(set_aside_new_creatures)
+            .add_systems(
+                Update,
+                record_frame.before(start_capture).run_if(not(replaying)),
+            )
+            .add_systems(
+                First,
+                replay_time
+                    .before(TimeSystem)
+                    .run_if(resource_exists::<Replay>),
+            )
+            .add_systems(PreUpdate, replay_frame.after(InputSystem).run_if(replaying))
+            .add_systems(Last, finish_replay.run_if(resource_exists::<Replay>));
+
+        if let Some(path) = std::env::var_os("BEVYMON_REPLAY") {
+            match Replay::load(&path) {
+                Ok(replay) => {
+                    app.insert_resource(replay);
+                }
+                Err(e) => error!("Unable to load replay {}: {e}", path.to_string_lossy()),
+            }
+        }
+    }
+}
+
+/// A single input that drove a capture.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RecordedInput {
+    Pressed(Vec2),
+    Moved(Vec2),
+    Lifted,
+    Cancelled(CancelReason),
+    Restarted,
+    Assist(Assist),
+}
+
+/// A creature as it was when the session started.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedCreature {
+    pub position: Vec2,
+    pub requirements: u32,
+    pub progress: u32,
+    pub creature_type: Option<CreatureType>,
+    pub collider: Option<RecordedCollider>,
+    pub decay: Option<RecordedDecay>,
+}
+
+/// The shape of a creature's [`Collider`]. Shapes other than circles are recorded as their
+/// bounding rectangle.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RecordedCollider {
+    Circle(f32),
+    Rectangle(Vec2),
+}
+
+impl RecordedCollider {
+    fn of(collider: &Collider) -> Self {
+        match collider.shape().as_ball() {
+            Some(ball) => RecordedCollider::Circle(ball.radius),
+            None => {
+                let size = collider.shape().compute_local_aabb().extents();
+                RecordedCollider::Rectangle(Vec2::new(size.x, size.y))
+            }
+        }
+    }
+
+    fn collider(self) -> Collider {
+        match self {
+            RecordedCollider::Circle(radius) => Collider::circle(radius),
+            RecordedCollider::Rectangle(size) => Collider::rectangle(size.x, size.y),
+        }
+    }
+}
+
+/// The timings of a creature's [`CaptureDecay`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RecordedDecay {
+    pub delay: Duration,
+    pub interval: Duration,
+}
+
+/// Everything that happened during one update of a session.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RecordedFrame {
+    /// How much real time passed during the update. Recorded to the nanosecond so that replays
+    /// match.
+    pub delta: Duration,
+
+    /// How fast virtual time ran compared to real time, which is zero while paused.
+    pub time_scale: f64,
+    pub inputs: Vec<RecordedInput>,
+
+    /// Creatures that moved since the last frame, as their index in [`Recording::creatures`].
+    pub moved: Vec<(u16, Vec2)>,
+}
+
+/// A single capture session, from the line being pressed until it is gone.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Recording {
+    /// The styler the session was drawn with.
+    pub styler: Option<Styler>,
+    pub creatures: Vec<RecordedCreature>,
+    pub frames: Vec<RecordedFrame>,
+}
+
+impl Recording {
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::decode(&mut fs::File::open(path)?)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut bytes = vec![];
+        self.encode(&mut bytes)?;
+        fs::write(path, bytes)
+    }
+
+    /// Writes the recording as little-endian binary. Every count is written before the values it
+    /// counts.
+    pub fn encode(&self, writer: &mut impl Write) -> io::Result<()> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&[VERSION])?;
+
+        write_option(writer, self.styler.as_ref(), |writer, styler| {
+            writer.write_all(&styler.power.to_le_bytes())?;
+            write_option(writer, styler.max_line_length, |writer, length| {
+                let length =
+                    u32::try_from(length).map_err(|_| invalid_data("Line too long to record"))?;
+                writer.write_all(&length.to_le_bytes())
+            })?;
+            writer.write_all(&styler.energy.to_le_bytes())?;
+            writer.write_all(&styler.width.to_le_bytes())?;
+            let skin = match styler.skin {
+                StylerSkin::Standard => 0,
+                StylerSkin::Ember => 1,
+                StylerSkin::Tide => 2,
+                StylerSkin::Aurora => 3,
+            };
+            writer.write_all(&[skin])
+        })?;
+
+        write_count(writer, self.creatures.len())?;
+        for creature in &self.creatures {
+            write_vec2(writer, creature.position)?;
+            writer.write_all(&creature.requirements.to_le_bytes())?;
+            writer.write_all(&creature.progress.to_le_bytes())?;
+            write_option(writer, creature.creature_type, |writer, creature_type| {
+                let creature_type = match creature_type {
+                    CreatureType::Normal => 0,
+                    CreatureType::Fire => 1,
+                    CreatureType::Water => 2,
+                    CreatureType::Grass => 3,
+                    CreatureType::Electric => 4,
+                };
+                writer.write_all(&[creature_type])
+            })?;
+            write_option(
+                writer,
+                creature.collider,
+                |writer, collider| match collider {
+                    RecordedCollider::Circle(radius) => {
+                        writer.write_all(&[0])?;
+                        writer.write_all(&radius.to_le_bytes())
+                    }
+                    RecordedCollider::Rectangle(size) => {
+                        writer.write_all(&[1])?;
+                        write_vec2(writer, size)
+                    }
+                },
+            )?;
+            write_option(writer, creature.decay, |writer, decay| {
+                write_duration(writer, decay.delay)?;
+                write_duration(writer, decay.interval)
+            })?;
+        }
+
+        writer.write_all(&(self.frames.len() as u32).to_le_bytes())?;
+        for frame in &self.frames {
+            write_duration(writer, frame.delta)?;
+            writer.write_all(&frame.time_scale.to_le_bytes())?;
+
+            write_count(writer, frame.inputs.len())?;
+            for input in &frame.inputs {
+                match input {
+                    RecordedInput::Pressed(point) => {
+                        writer.write_all(&[0])?;
+                        write_vec2(writer, *point)?;
+                    }
+                    RecordedInput::Moved(point) => {
+                        writer.write_all(&[1])?;
+                        write_vec2(writer, *point)?;
+                    }
+                    RecordedInput::Lifted => writer.write_all(&[2])?,
+                    RecordedInput::Cancelled(reason) => {
+                        let reason = match reason {
+                            CancelReason::CursorLeft => 0,
+                            CancelReason::GracePeriodExpired => 1,
+                        };
+                        writer.write_all(&[3, reason])?;
+                    }
+                    RecordedInput::Restarted => writer.write_all(&[4])?,
+                    RecordedInput::Assist(assist) => {
+                        writer.write_all(&[5])?;
+                        match assist {
+                            Assist::ProgressBurst { radius, amount } => {
+                                writer.write_all(&[0])?;
+                                writer.write_all(&radius.to_le_bytes())?;
+                                writer.write_all(&amount.to_le_bytes())?;
+                            }
+                            Assist::Slow { factor, seconds } => {
+                                writer.write_all(&[1])?;
+                                writer.write_all(&factor.to_le_bytes())?;
+                                writer.write_all(&seconds.to_le_bytes())?;
+                            }
+                            Assist::Shield => writer.write_all(&[2])?,
+                            Assist::SlowMotion { scale, seconds } => {
+                                writer.write_all(&[3])?;
+                                writer.write_all(&scale.to_le_bytes())?;
+                                writer.write_all(&seconds.to_le_bytes())?;
+                            }
+                        }
+                    }
+                }
+            }
+
+            write_count(writer, frame.moved.len())?;
+            for (index, position) in &frame.moved {
+                writer.write_all(&index.to_le_bytes())?;
+                write_vec2(writer, *position)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn decode(reader: &mut impl Read) -> io::Result<Self> {
+        let mut magic = [0; 4];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid_data("Not a capture recording"));
+        }
+
+        let [version] = read_bytes(reader)?;
+        if version != VERSION {
+            return Err(invalid_data("Unsupported capture recording version"));
+        }
+
+        let styler = read_option(reader, |reader| {
+            Ok(Styler {
+                power: u32::from_le_bytes(read_bytes(reader)?),
+                max_line_length: read_option(reader, |reader| {
+                    Ok(u32::from_le_bytes(read_bytes(reader)?) as usize)
+                })?,
+                energy: f32::from_le_bytes(read_bytes(reader)?),
+                width: f32::from_le_bytes(read_bytes(reader)?),
+                skin: match read_bytes(reader)? {
+                    [0] => StylerSkin::Standard,
+                    [1] => StylerSkin::Ember,
+                    [2] => StylerSkin::Tide,
+                    [3] => StylerSkin::Aurora,
+                    _ => return Err(invalid_data("Unknown styler skin")),
+                },
+            })
+        })?;
+
+        let creatures = (0..read_count(reader)?)
+            .map(|_| {
+                Ok(RecordedCreature {
+                    position: read_vec2(reader)?,
+                    requirements: u32::from_le_bytes(read_bytes(reader)?),
+                    progress: u32::from_le_bytes(read_bytes(reader)?),
+                    creature_type: read_option(reader, |reader| match read_bytes(reader)? {
+                        [0] => Ok(CreatureType::Normal),
+                        [1] => Ok(CreatureType::Fire),
+                        [2] => Ok(CreatureType::Water),
+                        [3] => Ok(CreatureType::Grass),
+                        [4] => Ok(CreatureType::Electric),
+                        _ => Err(invalid_data("Unknown creature type")),
+                    })?,
+                    collider: read_option(reader, |reader| match read_bytes(reader)? {
+                        [0] => Ok(RecordedCollider::Circle(f32::from_le_bytes(read_bytes(
+                            reader,
+                        )?))),
+                        [1] => Ok(RecordedCollider::Rectangle(read_vec2(reader)?)),
+                        _ => Err(invalid_data("Unknown collider shape")),
+                    })?,
+                    decay: read_option(reader, |reader| {
+                        Ok(RecordedDecay {
+                            delay: read_duration(reader)?,
+                            interval: read_duration(reader)?,
+                        })
+                    })?,
+                })
+            })
+            .collect::<io::Result<_>>()?;
+
+        let frames = (0..u32::from_le_bytes(read_bytes(reader)?))
+            .map(|_| {
+                let delta = read_duration(reader)?;
+                let time_scale = f64::from_le_bytes(read_bytes(reader)?);
+
+                let inputs = (0..read_count(reader)?)
+                    .map(|_| {
+                        let [kind] = read_bytes(reader)?;
+                        match kind {
+                            0 => Ok(RecordedInput::Pressed(read_vec2(reader)?)),
+                            1 => Ok(RecordedInput::Moved(read_vec2(reader)?)),
+                            2 => Ok(RecordedInput::Lifted),
+                            3 => match read_bytes(reader)? {
+                                [0] => Ok(RecordedInput::Cancelled(CancelReason::CursorLeft)),
+                                [1] => {
+                                    Ok(RecordedInput::Cancelled(CancelReason::GracePeriodExpired))
+                                }
+                                _ => Err(invalid_data("Unknown cancel reason")),
+                            },
+                            4 => Ok(RecordedInput::Restarted),
+                            5 => Ok(RecordedInput::Assist(match read_bytes(reader)? {
+                                [0] => Assist::ProgressBurst {
+                                    radius: f32::from_le_bytes(read_bytes(reader)?),
+                                    amount: u32::from_le_bytes(read_bytes(reader)?),
+                                },
+                                [1] => Assist::Slow {
+                                    factor: f32::from_le_bytes(read_bytes(reader)?),
+                                    seconds: f32::from_le_bytes(read_bytes(reader)?),
+                                },
+                                [2] => Assist::Shield,
+                                [3] => Assist::SlowMotion {
+                                    scale: f32::from_le_bytes(read_bytes(reader)?),
+                                    seconds: f32::from_le_bytes(read_bytes(reader)?),
+                                },
+                                _ => return Err(invalid_data("Unknown assist")),
+                            })),
+                            _ => Err(invalid_data("Unknown recorded input")),
+                        }
+                    })
+                    .collect::<io::Result<_>>()?;
+
+                let moved = (0..read_count(reader)?)
+                    .map(|_| Ok((u16::from_le_bytes(read_bytes(reader)?), read_vec2(reader)?)))
+                    .collect::<io::Result<_>>()?;
+
+                Ok(RecordedFrame {
+                    delta,
+                    time_scale,
+                    inputs,
+                    moved,
+                })
+            })
+            .collect::<io::Result<_>>()?;
+
+        Ok(Self {
+            styler,
+            creatures,
+            frames,
+        })
+    }
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+fn write_count(writer: &mut impl Write, count: usize) -> io::Result<()> {
+    let count = u16::try_from(count).map_err(|_| invalid_data("Too many values to record"))?;
+    writer.write_all(&count.to_le_bytes())
+}
+
+fn write_vec2(writer: &mut impl Write, value: Vec2) -> io::Result<()> {
+    writer.write_all(&value.x.to_le_bytes())?;
+    writer.write_all(&value.y.to_le_bytes())
+}
+
+/// Writes durations to the nanosecond, so that replays step time exactly as it was recorded.
+fn write_duration(writer: &mut impl Write, duration: Duration) -> io::Result<()> {
+    let nanos = u64::try_from(duration.as_nanos())
+        .map_err(|_| invalid_data("Duration too long to record"))?;
+    writer.write_all(&nanos.to_le_bytes())
+}
+
+/// Writes whether there is a value, followed by the value if there is one.
+fn write_option<W: Write, T>(
+    writer: &mut W,
+    value: Option<T>,
+    write: impl FnOnce(&mut W, T) -> io::Result<()>,
+) -> io::Result<()> {
+    match value {
+        Some(value) => {
+            writer.write_all(&[1])?;
+            write(writer, value)
+        }
+        None => writer.write_all(&[0]),
+    }
+}
+
+fn read_bytes<const N: usize>(reader: &mut impl Read) -> io::Result<[u8; N]> {
+    let mut bytes = [0; N];
+    reader.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+fn read_count(reader: &mut impl Read) -> io::Result<u16> {
+    Ok(u16::from_le_bytes(read_bytes(reader)?))
+}
+
+fn read_duration(reader: &mut impl Read) -> io::Result<Duration> {
+    Ok(Duration::from_nanos(u64::from_le_bytes(read_bytes(
+        reader,
+    )?)))
+}
+
+fn read_option<R: Read, T>(
+    reader: &mut R,
+    read: impl FnOnce(&mut R) -> io::Result<T>,
+) -> io::Result<Option<T>> {
+    match read_bytes(reader)? {
+        [0] => Ok(None),
+        [1] => Ok(Some(read(reader)?)),
+        _ => Err(invalid_data("Expected an optional value")),
+    }
+}
+
+fn read_vec2(reader: &mut impl Read) -> io::Result<Vec2> {
+    Ok(Vec2::new(
+        f32::from_le_bytes(read_bytes(reader)?),
+        f32::from_le_bytes(read_bytes(reader)?),
+    ))
+}
+
+/// Records every capture session, saving each one to `directory` in the background when it
+/// ends.
+#[derive(Resource, Debug, Default)]
+pub struct Recorder {
+    /// Where recordings are saved. Nothing is saved when this is `None`.
+    pub directory: Option<PathBuf>,
+
+    /// The most recently finished recording.
+    pub last: Option<Recording>,
+
+    session: Option<Session>,
+}
+
+/// The recording in progress, along with the creatures it is tracking.
+#[derive(Debug)]
+struct Session {
+    recording: Recording,
+    creatures: Vec<(Entity, Vec2)>,
+}
+
+impl Recorder {
+    pub fn saving_to(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            directory: Some(directory.into()),
+            ..default()
+        }
+    }
+
+    fn finish(&mut self) {
+        let Some(session) = self.session.take() else {
+            return;
+        };
+
+        if let Some(directory) = &self.directory {
+            let millis = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis();
+            let path = directory.join(format!("capture-{millis}.{RECORDING_EXTENSION}"));
+
+            let mut bytes = vec![];
+            match session.recording.encode(&mut bytes) {
+                Ok(()) => IoTaskPool::get()
+                    .spawn(async move {
+                        match save::write(&path, bytes) {
+                            Ok(()) => info!("Saved capture recording to {}", path.display()),
+                            Err(e) => error!("Unable to save capture recording: {e}"),
+                        }
+                    })
+                    .detach(),
+                Err(e) => error!("Unable to save capture recording: {e}"),
+            }
+        }
+
+        self.last = Some(session.recording);
+    }
+}
+
+/// Feeds a [`Recording`] back through the capture rules, one frame per update.
+#[derive(Resource, Debug)]
+pub struct Replay {
+    recording: Recording,
+    next_frame: usize,
+
+    /// The creatures spawned for the replay, in the same order as [`Recording::creatures`].
+    pub creatures: Vec<Entity>,
+
+    /// The player's own styler, put back once the replay has finished with the recorded one.
+    styler: Option<Styler>,
+}
+
+impl Replay {
+    pub fn new(recording: Recording) -> Self {
+        Self {
+            recording,
+            next_frame: 0,
+            creatures: vec![],
+            styler: None,
+        }
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(Self::new(Recording::load(path)?))
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.next_frame >= self.recording.frames.len()
+    }
+}
+
+/// A creature that was spawned to stand in for one from a [`Recording`].
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct ReplayedCreature(pub usize);
+
+/// Holds the recorded styler during a replay when nothing else holds one.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct ReplayedStyler;
+
+/// Holds the [`CaptureProgress`] of a creature that isn't part of the replay, which keeps it out
+/// of the capture until the replay has finished.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct SetAside(CaptureProgress);
+
+/// Whether a replay is driving the capture instead of the player.
+pub(crate) fn replaying(replay: Option<Res<Replay>>) -> bool {
+    replay.is_some_and(|replay| !replay.is_finished())
+}
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn record_frame(
+    mut recorder: ResMut<Recorder>,
+    mut pressed: EventReader<CapturePointPressed>,
+    mut moved: EventReader<CapturePointMoved>,
+    mut restarted: EventReader<CapturePointRestarted>,
+    mut lifted: EventReader<CapturePointLifted>,
+    mut cancelled: EventReader<CaptureCancelled>,
+    mut assists: EventReader<AssistPressed>,
+    real_time: Res<Time<Real>>,
+    time: Res<Time<Virtual>>,
+    lines: Query<(), With<CaptureLine>>,
+    styler: Option<Single<&Styler>>,
+    creatures: Query<
+        (
+            Entity,
+            &Transform,
+            &CaptureRequirements,
+            &CaptureProgress,
+            Option<&CreatureType>,
+            Option<&Collider>,
+            Option<&CaptureDecay>,
+        ),
+        (With<Creature>, Without<Captured>),
+    >,
+    positions: Query<&Transform, With<Creature>>,
+) {
+    let pressed = pressed.read().map(|pressed| pressed.0).collect::<Vec<_>>();
+    let lifted = lifted.read().count() > 0;
+    let cancelled = cancelled.read().map(|cancelled| cancelled.0).next();
+
+    // The line is gone without being lifted, so it must have been destroyed.
+    if pressed.is_empty() && lines.is_empty() {
+        recorder.finish();
+    }
+
+    if recorder.session.is_none() {
+        if pressed.is_empty() {
+            moved.clear();
+            restarted.clear();
+            assists.clear();
+            return;
+        }
+
+        recorder.session = Some(Session {
+            recording: Recording {
+                styler: styler.map(|styler| styler.clone()),
+                creatures: creatures
+                    .iter()
+                    .map(
+                        |(_, transform, requirements, progress, creature_type, collider, decay)| {
+                            RecordedCreature {
+                                position: transform.translation.xy(),
+                                requirements: requirements.0,
+                                progress: progress.progress,
+                                creature_type: creature_type.copied(),
+                                collider: collider.map(RecordedCollider::of),
+                                decay: decay.map(|decay| RecordedDecay {
+                                    delay: decay.delay,
+                                    interval: decay.timer.duration(),
+                                }),
+                            }
+                        },
+                    )
+                    .collect(),
+                frames: vec![],
+            },
+            creatures: creatures
+                .iter()
+                .map(|(entity, transform, ..)| (entity, transform.translation.xy()))
+                .collect(),
+        });
+    }
+
+    let Some(session) = recorder.session.as_mut() else {
+        return;
+    };
+
+    let mut frame = RecordedFrame {
+        delta: real_time.delta(),
+        time_scale: time.effective_speed_f64(),
+        inputs: pressed.into_iter().map(RecordedInput::Pressed).collect(),
+        moved: vec![],
+    };
+    frame
+        .inputs
+        .extend(restarted.read().map(|_| RecordedInput::Restarted));
+    frame
+        .inputs
+        .extend(moved.read().map(|moved| RecordedInput::Moved(moved.0)));
+    frame.inputs.extend(
+        assists
+            .read()
+            .map(|AssistPressed(assist)| RecordedInput::Assist(assist.clone())),
+    );
+    if lifted {
+        frame.inputs.push(RecordedInput::Lifted);
+    }
+    if let Some(reason) = cancelled {
+        frame.inputs.push(RecordedInput::Cancelled(reason));
+    }
+
+    for (index, (entity, last_position)) in session.creatures.iter_mut().enumerate() {
+        let Ok(transform) = positions.get(*entity) else {
+            continue;
+        };
+        let position = transform.translation.xy();
+        if position != *last_position {
+            *last_position = position;
+            frame.moved.push((index as u16, position));
+        }
+    }
+
+    session.recording.frames.push(frame);
+
+    if lifted || cancelled.is_some() {
+        recorder.finish();
+    }
+}
+
+fn replay_time(mut commands: Commands, replay: Res<Replay>, mut time: ResMut<Time<Virtual>>) {
+    match replay.recording.frames.get(replay.next_frame) {
+        Some(frame) => {
+            commands.insert_resource(TimeUpdateStrategy::ManualDuration(frame.delta));
+            time.set_relative_speed_f64(frame.time_scale);
+        }
+        None => {
+            commands.insert_resource(TimeUpdateStrategy::Automatic);
+            commands.remove_resource::<Replay>();
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn replay_frame(
+    mut commands: Commands,
+    mut replay: ResMut<Replay>,
+    mut pressed: EventWriter<CapturePointPressed>,
+    mut moved: EventWriter<CapturePointMoved>,
+    mut restarted: EventWriter<CapturePointRestarted>,
+    mut lifted: EventWriter<CapturePointLifted>,
+    mut cancelled: EventWriter<CaptureCancelled>,
+    mut assists: EventWriter<AssistPressed>,
+    mut creatures: Query<&mut Transform, With<ReplayedCreature>>,
+    live_creatures: Query<Entity, (With<CaptureProgress>, Without<ReplayedCreature>)>,
+    styler: Option<Single<&mut Styler>>,
+) {
+    let replay = replay.as_mut();
+    if replay.next_frame == 0 {
+        for creature in &live_creatures {
+            set_aside(&mut commands, creature);
+        }
+        match (styler, replay.recording.styler.clone()) {
+            (Some(mut styler), Some(recorded)) => {
+                replay.styler = Some(std::mem::replace(&mut *styler, recorded));
+            }
+            (None, Some(recorded)) => {
+                commands.spawn((Name::from("Replayed Styler"), ReplayedStyler, recorded));
+            }
+            (_, None) => {}
+        }
+        replay.creatures = replay
+            .recording
+            .creatures
+            .iter()
+            .enumerate()
+            .map(|(index, creature)| {
+                let mut entity = commands.spawn((
+                    Name::from("Replayed Creature"),
+                    ReplayedCreature(index),
+                    Creature,
+                    CaptureRequirements(creature.requirements),
+                    CaptureProgress {
+                        progress: creature.progress,
+                        last_loop: None,
+                    },
+                    Transform::from_translation(creature.position.extend(0.)),
+                ));
+                if let Some(creature_type) = creature.creature_type {
+                    entity.insert(creature_type);
+                }
+                if let Some(collider) = creature.collider {
+                    entity.insert(collider.collider());
+                }
+                if let Some(decay) = creature.decay {
+                    entity.insert(CaptureDecay::new(decay.delay, decay.interval));
+                }
+                entity.id()
+            })
+            .collect();
+    }
+
+    let frame = &replay.recording.frames[replay.next_frame];
+    for input in &frame.inputs {
+        match input {
+            RecordedInput::Pressed(point) => {
+                pressed.write(CapturePointPressed(*point));
+            }
+            RecordedInput::Moved(point) => {
+                moved.write(CapturePointMoved(*point));
+            }
+            RecordedInput::Lifted => {
+                lifted.write(CapturePointLifted);
+            }
+            RecordedInput::Cancelled(reason) => {
+                cancelled.write(CaptureCancelled(*reason));
+            }
+            RecordedInput::Restarted => {
+                restarted.write(CapturePointRestarted);
+            }
+            RecordedInput::Assist(assist) => {
+                assists.write(AssistPressed(assist.clone()));
+            }
+        }
+    }
+
+    for (index, position) in &frame.moved {
+        let Some(entity) = replay.creatures.get(*index as usize) else {
+            continue;
+        };
+        if let Ok(mut transform) = creatures.get_mut(*entity) {
+            transform.translation = position.extend(transform.translation.z);
+        }
+    }
+
+    replay.next_frame += 1;
+}
+
+fn set_aside(commands: &mut Commands, creature: Entity) {
+    commands
+        .entity(creature)
+        .queue(|mut creature: EntityWorldMut| {
+            if let Some(progress) = creature.take::<CaptureProgress>() {
+                creature.insert(SetAside(progress));
+            }
+        });
+}
+
+/// Sets aside creatures that turn up during a replay, such as those spawned with the level.
+fn set_aside_new_creatures(
+    added: Trigger<OnAdd, CaptureProgress>,
+    mut commands: Commands,
+    replay: Option<Res<Replay>>,
+    replayed: Query<(), With<ReplayedCreature>>,
+) {
+    let replaying = replay.is_some_and(|replay| !replay.is_finished());
+    if replaying && !replayed.contains(added.target()) {
+        set_aside(&mut commands, added.target());
+    }
+}
+
+/// Puts back the player's styler, the creatures that were set aside and the speed of time once the
+/// last recorded frame has been replayed.
+fn finish_replay(
+    mut commands: Commands,
+    mut replay: ResMut<Replay>,
+    mut time: ResMut<Time<Virtual>>,
+    styler: Option<Single<&mut Styler>>,
+    replayed: Query<Entity, With<ReplayedStyler>>,
+    set_aside: Query<Entity, With<SetAside>>,
+) {
+    if !replay.is_finished() {
+        return;
+    }
+
+    if let (Some(mut styler), Some(own)) = (styler, replay.styler.take()) {
+        **styler = own;
+    }
+    for replayed in &replayed {
+        commands.entity(replayed).despawn();
+    }
+    for creature in &set_aside {
+        commands
+            .entity(creature)
+            .queue(|mut creature: EntityWorldMut| {
+                if let Some(SetAside(progress)) = creature.take::<SetAside>() {
+                    creature.insert(progress);
+                }
+            });
+    }
+    // Slow motion that is still running sets its own speed again.
+    time.set_relative_speed(1.);
+}
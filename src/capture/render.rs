@@ -0,0 +1,297 @@
+use crate::capture::CaptureLine;
+use bevy::asset::RenderAssetUsages;
+use bevy::prelude::*;
+use bevy::render::mesh::{PrimitiveTopology, VertexAttributeValues};
+use bevy::render::render_resource::{AsBindGroup, ShaderRef};
+use bevy::sprite::{AlphaMode2d, Material2d, Material2dPlugin};
+
+/// How far from being dropped a part of the line starts fading out.
+const LINE_FADE_LENGTH: f32 = 100.;
+
+/// How faded the oldest part of the line can become.
+const MIN_LINE_ALPHA: f32 = 0.15;
+
+/// How much wider than the line itself the mesh is, to leave room for the glow.
+const GLOW_SCALE: f32 = 2.;
+
+/// How long a miter can be, relative to the line's half width, before a round join is used.
+const MITER_LIMIT: f32 = 2.;
+
+/// How many segments make up a round join.
+const ROUND_SEGMENTS: usize = 4;
+
+pub struct LineRenderPlugin;
+impl Plugin for LineRenderPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(Material2dPlugin::<CaptureLineMaterial>::default())
+            .add_systems(
+                Update,
+                (attach_line_mesh, update_line_mesh)
+                    .chain()
+                    .run_if(any_with_component::<CaptureLine>),
+            );
+    }
+}
+
+#[derive(Asset, TypePath, AsBindGroup, Debug, Clone)]
+pub struct CaptureLineMaterial {
+    #[uniform(0)]
+    glow_color: LinearRgba,
+
+    /// The fraction of the mesh's width that is the solid core of the line.
+    #[uniform(0)]
+    core_width: f32,
+}
+
+impl Material2d for CaptureLineMaterial {
+    fn fragment_shader() -> ShaderRef {
+        "shaders/capture_line.wgsl".into()
+    }
+
+    fn alpha_mode(&self) -> AlphaMode2d {
+        AlphaMode2d::Blend
+    }
+}
+
+/// A single vertex of the line mesh, before it is coloured.
+#[derive(Debug, Clone, Copy)]
+struct LineVertex {
+    position: Vec2,
+
+    /// Which edge of the line the vertex is on, where `0.` is the left and `1.` is the right.
+    side: f32,
+}
+
+/// The vertices for each point of a [`CaptureLine`], kept so that only the points that changed
+/// need their joins recalculated.
+#[derive(Component, Debug, Default)]
+struct LineGeometry {
+    points: Vec<Vec2>,
+    half_width: f32,
+    joins: Vec<Vec<LineVertex>>,
+}
+
+impl LineGeometry {
+    fn update(&mut self, line: &[Vec2], half_width: f32) {
+        if half_width != self.half_width {
+            self.points.clear();
+            self.joins.clear();
+            self.half_width = half_width;
+        }
+
+        // Points dropped off of the start of the line only change the join of the new first point.
+        let removed = match line.first() {
+            Some(first) => self
+                .points
+                .iter()
+                .position(|point| point == first)
+                .unwrap_or(self.points.len()),
+            None => self.points.len(),
+        };
+        self.points.drain(..removed);
+        self.joins.drain(..removed);
+
+        // The last shared point was the end of the line, so its join needs redoing as well.
+        let shared = self
+            .points
+            .iter()
+            .zip(line)
+            .take_while(|(old, new)| old == new)
+            .count();
+        let keep = shared.saturating_sub(1);
+
+        self.joins.truncate(keep);
+        if removed > 0 && keep > 0 {
+            self.joins[0] = join(line, 0, half_width);
+        }
+        for i in keep..line.len() {
+            self.joins.push(join(line, i, half_width));
+        }
+
+        self.points.clear();
+        self.points.extend_from_slice(line);
+    }
+}
+
+/// Calculates the vertices that make up the join at `line[i]`, in triangle strip order.
+fn join(line: &[Vec2], i: usize, half_width: f32) -> Vec<LineVertex> {
+    let point = line[i];
+    let incoming = i
+        .checked_sub(1)
+        .map(|previous| (point - line[previous]).normalize_or_zero())
+        .unwrap_or(Vec2::ZERO);
+    let outgoing = line
+        .get(i + 1)
+        .map(|next| (*next - point).normalize_or_zero())
+        .unwrap_or(Vec2::ZERO);
+
+    let edge = |offset: Vec2| {
+        [
+            LineVertex {
+                position: point + offset,
+                side: 0.,
+            },
+            LineVertex {
+                position: point - offset,
+                side: 1.,
+            },
+        ]
+    };
+
+    // Caps, and points that are on top of their neighbours.
+    if incoming == Vec2::ZERO || outgoing == Vec2::ZERO {
+        let direction = if incoming == Vec2::ZERO {
+            outgoing
+        } else {
+            incoming
+        };
+        return edge(direction.perp() * half_width).to_vec();
+    }
+
+    let miter = (incoming.perp() + outgoing.perp()).normalize_or_zero();
+    let miter_length = half_width / miter.dot(incoming.perp());
+    if miter != Vec2::ZERO && miter_length <= half_width * MITER_LIMIT {
+        return edge(miter * miter_length).to_vec();
+    }
+
+    // The corner is too sharp for a miter, so fan around the outside of it instead.
+    let outer_side = if incoming.perp_dot(outgoing) > 0. {
+        -1.
+    } else {
+        1.
+    };
+    let from = incoming.perp() * half_width * outer_side;
+    let to = outgoing.perp() * half_width * outer_side;
+    let angle = from.angle_to(to);
+    let inner = LineVertex {
+        position: point - (from + to).normalize_or_zero() * half_width,
+        side: if outer_side > 0. { 1. } else { 0. },
+    };
+
+    (0..=ROUND_SEGMENTS)
+        .flat_map(|step| {
+            let rotation = Vec2::from_angle(angle * step as f32 / ROUND_SEGMENTS as f32);
+            let outer = LineVertex {
+                position: point + rotation.rotate(from),
+                side: 1. - inner.side,
+            };
+            if outer_side > 0. {
+                [outer, inner]
+            } else {
+                [inner, outer]
+            }
+        })
+        .collect()
+}
+
+/// The colour of each point of the line, fading out the oldest part of the line as it gets close
+/// to being dropped.
+fn point_colors(line: &CaptureLine) -> Vec<LinearRgba> {
+    let (start_color, end_color) = match (line.start_color, line.end_color) {
+        (Some(start), Some(end)) => (start.to_linear(), end.to_linear()),
+        (Some(c), None) | (None, Some(c)) => (c.to_linear(), c.to_linear()),
+        (None, None) => (LinearRgba::WHITE, LinearRgba::WHITE),
+    };
+
+    let remaining = line.remaining_length();
+    let mut travelled = 0.;
+    let mut previous = line.line.first().copied().unwrap_or_default();
+    let points_count = line.line.len() as f32;
+
+    line.line
+        .iter()
+        .enumerate()
+        .map(|(i, point)| {
+            travelled += point.distance(previous);
+            previous = *point;
+
+            let mut color = start_color.mix(&end_color, i as f32 / points_count);
+            if let Some(remaining) = remaining {
+                color.alpha *=
+                    ((remaining + travelled) / LINE_FADE_LENGTH).clamp(MIN_LINE_ALPHA, 1.);
+            }
+            color
+        })
+        .collect()
+}
+
+fn attach_line_mesh(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<CaptureLineMaterial>>,
+    lines: Query<(Entity, &CaptureLine), Without<LineGeometry>>,
+) {
+    for (entity, line) in lines {
+        let glow_color = line
+            .end_color
+            .or(line.start_color)
+            .unwrap_or(Color::WHITE)
+            .to_linear()
+            .with_alpha(0.5);
+
+        commands.entity(entity).insert((
+            LineGeometry::default(),
+            Mesh2d(
+                meshes.add(
+                    Mesh::new(
+                        PrimitiveTopology::TriangleStrip,
+                        RenderAssetUsages::default(),
+                    )
+                    .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, Vec::<[f32; 3]>::new())
+                    .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, Vec::<[f32; 2]>::new())
+                    .with_inserted_attribute(Mesh::ATTRIBUTE_COLOR, Vec::<[f32; 4]>::new()),
+                ),
+            ),
+            MeshMaterial2d(materials.add(CaptureLineMaterial {
+                glow_color,
+                core_width: 1. / GLOW_SCALE,
+            })),
+        ));
+    }
+}
+
+fn update_line_mesh(
+    mut meshes: ResMut<Assets<Mesh>>,
+    lines: Query<(&CaptureLine, &mut LineGeometry, &Mesh2d), Changed<CaptureLine>>,
+) {
+    for (line, mut geometry, mesh) in lines {
+        let Some(mesh) = meshes.get_mut(&mesh.0) else {
+            continue;
+        };
+
+        geometry.update(&line.line, line.width / 2. * GLOW_SCALE);
+
+        let colors = point_colors(line);
+        let points_count = line.line.len().saturating_sub(1).max(1) as f32;
+        let vertices = || {
+            geometry
+                .joins
+                .iter()
+                .enumerate()
+                .flat_map(|(i, join)| join.iter().map(move |vertex| (i, vertex)))
+        };
+
+        // The attributes are refilled in place so that their allocations are reused between
+        // updates.
+        if let Some(VertexAttributeValues::Float32x3(positions)) =
+            mesh.attribute_mut(Mesh::ATTRIBUTE_POSITION)
+        {
+            positions.clear();
+            positions.extend(vertices().map(|(_, vertex)| vertex.position.extend(0.).to_array()));
+        }
+
+        if let Some(VertexAttributeValues::Float32x2(uvs)) =
+            mesh.attribute_mut(Mesh::ATTRIBUTE_UV_0)
+        {
+            uvs.clear();
+            uvs.extend(vertices().map(|(i, vertex)| [i as f32 / points_count, vertex.side]));
+        }
+
+        if let Some(VertexAttributeValues::Float32x4(vertex_colors)) =
+            mesh.attribute_mut(Mesh::ATTRIBUTE_COLOR)
+        {
+            vertex_colors.clear();
+            vertex_colors.extend(vertices().map(|(i, _)| colors[i].to_f32_array()));
+        }
+    }
+}
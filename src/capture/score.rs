@@ -0,0 +1,192 @@
+use crate::capture::session::{CaptureSessionChanged, CapturedCreature, SessionState};
+use crate::capture::{CaptureLine, TakeDamage};
+use crate::creature::Species;
+use crate::save::SaveFile;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+pub struct ScorePlugin;
+impl Plugin for ScorePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<EncounterStats>()
+            .register_type::<BestScores>()
+            .init_resource::<EncounterStats>()
+            .init_resource::<BestScores>()
+            .add_systems(
+                Update,
+                track_line_length.run_if(any_with_component::<CaptureLine>),
+            )
+            .add_observer(track_encounter)
+            .add_observer(count_damage);
+    }
+}
+
+/// A letter grade for how well a capture went.
+#[derive(Reflect, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Rank {
+    S,
+    A,
+    B,
+    C,
+}
+
+impl Display for Rank {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let rank = match self {
+            Rank::S => "S",
+            Rank::A => "A",
+            Rank::B => "B",
+            Rank::C => "C",
+        };
+        f.write_str(rank)
+    }
+}
+
+/// How a single capture was performed.
+#[derive(Reflect, Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CaptureScore {
+    pub time: Duration,
+    pub loops: u32,
+    pub overshot_by: usize,
+    pub damage_taken: u32,
+    pub line_length: f32,
+}
+
+impl CaptureScore {
+    /// Combines every part of the capture into a single number, where higher is better.
+    pub fn points(&self) -> u32 {
+        let penalty = self.time.as_secs_f32() * 10.
+            + self.loops as f32 * 20.
+            + self.overshot_by as f32 * 40.
+            + self.damage_taken as f32 * 100.
+            + self.line_length / 10.;
+        (1000. - penalty).max(0.) as u32
+    }
+
+    pub fn rank(&self) -> Rank {
+        match self.points() {
+            800.. => Rank::S,
+            600.. => Rank::A,
+            400.. => Rank::B,
+            _ => Rank::C,
+        }
+    }
+}
+
+/// Everything that has happened during the session being drawn.
+#[derive(Resource, Reflect, Debug, Default)]
+#[reflect(Resource)]
+struct EncounterStats {
+    started: Option<Duration>,
+    loops: u32,
+    damage_taken: u32,
+    line_length: f32,
+}
+
+/// The best score the player has gotten for each species.
+#[derive(Resource, Reflect, Serialize, Deserialize, Debug, Default, Deref)]
+#[reflect(Resource)]
+pub struct BestScores(HashMap<Species, CaptureScore>);
+
+impl SaveFile for BestScores {
+    const FILE_NAME: &'static str = "best_scores.ron";
+    const DESCRIPTION: &'static str = "the best scores";
+}
+
+/// Represents when a captured creature has been scored.
+#[derive(Event, Debug)]
+pub struct CaptureScored {
+    pub captured: Entity,
+    pub species: Option<Species>,
+    pub score: CaptureScore,
+
+    /// Whether this is the best score for the species so far.
+    pub new_best: bool,
+}
+
+/// Keeps [`EncounterStats`] for each session, scoring whatever it captures when it ends. A session
+/// that fails takes its stats with it, so that they don't count against the next one.
+fn track_encounter(
+    changed: Trigger<CaptureSessionChanged>,
+    mut commands: Commands,
+    mut best_scores: ResMut<BestScores>,
+    mut stats: ResMut<EncounterStats>,
+    time: Res<Time>,
+    species: Query<&Species>,
+) {
+    match changed.state {
+        SessionState::Started => {
+            *stats = EncounterStats {
+                started: Some(time.elapsed()),
+                ..default()
+            };
+        }
+        SessionState::LoopClosed => stats.loops += 1,
+        SessionState::Drawing => {}
+        SessionState::Ended { .. } => {
+            for captured in changed.captured() {
+                score_capture(
+                    &mut commands,
+                    &mut best_scores,
+                    &stats,
+                    &time,
+                    &species,
+                    captured,
+                );
+            }
+            *stats = EncounterStats::default();
+        }
+    }
+}
+
+fn count_damage(damage: Trigger<TakeDamage>, mut stats: ResMut<EncounterStats>) {
+    stats.damage_taken += damage.amount;
+}
+
+fn track_line_length(mut stats: ResMut<EncounterStats>, line: Single<&CaptureLine>) {
+    stats.line_length = line.drawn_length;
+}
+
+fn score_capture(
+    commands: &mut Commands,
+    best_scores: &mut BestScores,
+    stats: &EncounterStats,
+    time: &Time,
+    species: &Query<&Species>,
+    captured: &CapturedCreature,
+) {
+    let score = CaptureScore {
+        time: stats
+            .started
+            .map(|started| time.elapsed() - started)
+            .unwrap_or_default(),
+        loops: stats.loops,
+        overshot_by: captured.overshot_by,
+        damage_taken: stats.damage_taken,
+        line_length: stats.line_length,
+    };
+
+    let species = species.get(captured.creature).ok().cloned();
+    let new_best = match &species {
+        Some(species) => {
+            let new_best = best_scores
+                .get(species)
+                .is_none_or(|best| score.points() > best.points());
+            if new_best {
+                best_scores.0.insert(species.clone(), score.clone());
+            }
+            new_best
+        }
+        None => false,
+    };
+
+    commands.trigger(CaptureScored {
+        captured: captured.creature,
+        species,
+        score,
+        new_best,
+    });
+}
@@ -0,0 +1,114 @@
+use bevy::prelude::*;
+
+/// Tracks each capture from the moment it's pressed until it ends, announcing every step as a
+/// [`CaptureSessionChanged`].
+pub struct SessionPlugin;
+impl Plugin for SessionPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<CaptureSession>()
+            .add_observer(apply_session_state);
+    }
+}
+
+/// One capture, from pressing to lifting. The capture line and styler are despawned with it.
+#[derive(Component, Reflect, Debug, Clone, PartialEq, Eq)]
+#[reflect(Component)]
+pub struct CaptureSession {
+    pub state: SessionState,
+}
+
+#[derive(Reflect, Debug, Clone, PartialEq, Eq)]
+pub enum SessionState {
+    /// The line has been pressed but hasn't moved yet.
+    Started,
+    Drawing,
+
+    /// The line crossed itself, looping whatever was inside.
+    LoopClosed,
+    Ended {
+        reason: EndReason,
+    },
+}
+
+/// Why a capture session ended.
+#[derive(Reflect, Debug, Clone, PartialEq, Eq)]
+pub enum EndReason {
+    /// The line was lifted without capturing anything, failing to capture every creature.
+    Lifted { failed: Vec<Entity> },
+
+    /// The line was hit by something that breaks it.
+    Hit,
+
+    /// The cursor left the window and the capture was cancelled.
+    LeftWindow,
+
+    /// The styler ran out of energy while drawing.
+    EnergyDepleted,
+
+    /// The line was lifted after capturing at least one creature, failing to capture the rest.
+    Success {
+        captured: Vec<CapturedCreature>,
+        failed: Vec<Entity>,
+    },
+}
+
+/// A creature captured when its session ended with [`EndReason::Success`].
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CapturedCreature {
+    pub creature: Entity,
+
+    /// How far the creature's progress went past what it required.
+    pub overshot_by: usize,
+}
+
+/// Represents when a capture session moves on to a new [`SessionState`].
+///
+/// Only ever triggered, for observers, and never again for a session once it has ended.
+#[derive(Event, Debug, Clone)]
+pub struct CaptureSessionChanged {
+    pub session: Entity,
+    pub state: SessionState,
+}
+
+impl CaptureSessionChanged {
+    pub fn ended(&self) -> Option<&EndReason> {
+        match &self.state {
+            SessionState::Ended { reason } => Some(reason),
+            _ => None,
+        }
+    }
+
+    /// The creatures captured by this change, which is only ever a successful ending.
+    pub fn captured(&self) -> &[CapturedCreature] {
+        match self.ended() {
+            Some(EndReason::Success { captured, .. }) => captured,
+            _ => &[],
+        }
+    }
+}
+
+/// Asks for a session to move on to a new state, which is ignored once it has ended.
+#[derive(Event, Debug)]
+struct SetSessionState(CaptureSessionChanged);
+
+/// Moves `session` on to `state` once commands are applied.
+pub(crate) fn set_session_state(commands: &mut Commands, session: Entity, state: SessionState) {
+    commands.trigger(SetSessionState(CaptureSessionChanged { session, state }));
+}
+
+fn apply_session_state(
+    trigger: Trigger<SetSessionState>,
+    mut commands: Commands,
+    mut sessions: Query<&mut CaptureSession>,
+) {
+    let change = trigger.event().0.clone();
+    let Ok(mut session) = sessions.get_mut(change.session) else {
+        return;
+    };
+    if matches!(session.state, SessionState::Ended { .. }) {
+        return;
+    }
+
+    session.state = change.state.clone();
+    commands.trigger(change);
+}
@@ -0,0 +1,311 @@
+pub(crate) mod menu;
+
+use crate::capture::config::CaptureConfig;
+use crate::capture::record::replaying;
+use crate::capture::score::{CaptureScored, Rank};
+use crate::capture::{StylerEnergy, STYLER_ENERGY};
+use crate::creature::types::CreatureType;
+use crate::save::SaveFile;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// The player's styler: its stats, the upgrades bought for it with experience, and its look.
+pub struct StylerPlugin;
+impl Plugin for StylerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<BuyUpgrade>()
+            .add_event::<ChooseSkin>()
+            .register_type::<Styler>()
+            .register_type::<StylerProgress>()
+            .init_resource::<StylerProgress>()
+            .add_observer(earn_experience)
+            .add_systems(
+                Update,
+                (
+                    buy_upgrades.run_if(on_event::<BuyUpgrade>),
+                    choose_skin.run_if(on_event::<ChooseSkin>),
+                    // A replay captures with the recorded styler, whatever has been bought since.
+                    apply_upgrades.run_if(not(replaying)),
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// The stats of the styler the player is capturing with, held by the player.
+#[derive(Component, Reflect, Debug, Clone, PartialEq)]
+#[reflect(Component)]
+pub struct Styler {
+    /// Capture progress given to every creature inside a loop.
+    pub power: u32,
+
+    /// How long a line can get before its oldest points are dropped, in world units.
+    pub max_line_length: Option<usize>,
+    pub energy: f32,
+    pub width: f32,
+    pub skin: StylerSkin,
+}
+
+impl Styler {
+    /// A styler without any upgrades, which is also what is captured with when nothing holds one.
+    pub fn base(config: &CaptureConfig) -> Self {
+        Self {
+            power: 1,
+            max_line_length: config.max_line_length,
+            energy: STYLER_ENERGY,
+            width: config.line_width,
+            skin: StylerSkin::Standard,
+        }
+    }
+}
+
+/// What the styler looks like and the type of its loops. Every skin but the standard one is
+/// unlocked by getting a capture of a high enough [`Rank`].
+#[derive(Reflect, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum StylerSkin {
+    #[default]
+    Standard,
+    Ember,
+    Tide,
+    Aurora,
+}
+
+impl StylerSkin {
+    pub const ALL: [StylerSkin; 4] = [
+        StylerSkin::Standard,
+        StylerSkin::Ember,
+        StylerSkin::Tide,
+        StylerSkin::Aurora,
+    ];
+
+    /// The rank a capture needs to get to unlock this skin.
+    pub fn required_rank(self) -> Option<Rank> {
+        match self {
+            StylerSkin::Standard => None,
+            StylerSkin::Ember => Some(Rank::B),
+            StylerSkin::Tide => Some(Rank::A),
+            StylerSkin::Aurora => Some(Rank::S),
+        }
+    }
+
+    /// The type the styler's loops count as for matchups.
+    pub fn element(self) -> CreatureType {
+        match self {
+            StylerSkin::Standard => CreatureType::Normal,
+            StylerSkin::Ember => CreatureType::Fire,
+            StylerSkin::Tide => CreatureType::Water,
+            StylerSkin::Aurora => CreatureType::Electric,
+        }
+    }
+
+    /// Asset path of the skin's sprite, where the standard skin uses [`CaptureConfig::styler_sprite`].
+    pub fn sprite(self, config: &CaptureConfig) -> String {
+        match self {
+            StylerSkin::Standard => config.styler_sprite.clone(),
+            StylerSkin::Ember => "stylers/ember.png".into(),
+            StylerSkin::Tide => "stylers/tide.png".into(),
+            StylerSkin::Aurora => "stylers/aurora.png".into(),
+        }
+    }
+}
+
+/// Something experience can be spent on to improve the [`Styler`].
+///
+/// Upgrades form a tree, where each can only be bought once the one it [requires] has been.
+///
+/// [requires]: Upgrade::requires
+#[derive(Reflect, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Upgrade {
+    Power,
+    Overcharge,
+    LongLine,
+    LongerLine,
+    WideLine,
+    Battery,
+    BigBattery,
+}
+
+impl Upgrade {
+    pub const ALL: [Upgrade; 7] = [
+        Upgrade::Power,
+        Upgrade::Overcharge,
+        Upgrade::LongLine,
+        Upgrade::LongerLine,
+        Upgrade::WideLine,
+        Upgrade::Battery,
+        Upgrade::BigBattery,
+    ];
+
+    /// How much experience the upgrade costs.
+    pub fn cost(self) -> u32 {
+        match self {
+            Upgrade::Power => 100,
+            Upgrade::Overcharge => 300,
+            Upgrade::LongLine => 50,
+            Upgrade::LongerLine => 150,
+            Upgrade::WideLine => 100,
+            Upgrade::Battery => 50,
+            Upgrade::BigBattery => 150,
+        }
+    }
+
+    /// The upgrade that has to be bought before this one.
+    pub fn requires(self) -> Option<Upgrade> {
+        match self {
+            Upgrade::Power | Upgrade::LongLine | Upgrade::Battery => None,
+            Upgrade::Overcharge => Some(Upgrade::Power),
+            Upgrade::LongerLine | Upgrade::WideLine => Some(Upgrade::LongLine),
+            Upgrade::BigBattery => Some(Upgrade::Battery),
+        }
+    }
+
+    fn apply(self, styler: &mut Styler) {
+        match self {
+            Upgrade::Power | Upgrade::Overcharge => styler.power += 1,
+            Upgrade::LongLine => {
+                styler.max_line_length = styler.max_line_length.map(|max| max + 100)
+            }
+            Upgrade::LongerLine => {
+                styler.max_line_length = styler.max_line_length.map(|max| max + 150)
+            }
+            Upgrade::WideLine => styler.width += 4.,
+            Upgrade::Battery => styler.energy += 25.,
+            Upgrade::BigBattery => styler.energy += 50.,
+        }
+    }
+}
+
+/// Experience earned from captures, what it has been spent on, and the skin chosen.
+#[derive(Resource, Reflect, Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[reflect(Resource)]
+#[serde(default)]
+pub struct StylerProgress {
+    /// Experience that hasn't been spent yet.
+    pub experience: u32,
+    pub upgrades: Vec<Upgrade>,
+
+    /// The best rank of any capture so far.
+    pub best_rank: Option<Rank>,
+    pub skin: StylerSkin,
+}
+
+impl SaveFile for StylerProgress {
+    const FILE_NAME: &'static str = "styler.ron";
+    const DESCRIPTION: &'static str = "the styler's progress";
+}
+
+impl StylerProgress {
+    pub fn has(&self, upgrade: Upgrade) -> bool {
+        self.upgrades.contains(&upgrade)
+    }
+
+    /// Why the upgrade can't be bought right now, if it can't.
+    pub fn can_buy(&self, upgrade: Upgrade) -> Result<(), String> {
+        if self.has(upgrade) {
+            return Err(format!("{upgrade:?} has already been bought"));
+        }
+        if let Some(required) = upgrade.requires().filter(|required| !self.has(*required)) {
+            return Err(format!("{upgrade:?} needs {required:?} first"));
+        }
+        if self.experience < upgrade.cost() {
+            return Err(format!(
+                "{upgrade:?} costs {} experience, but there is only {}",
+                upgrade.cost(),
+                self.experience
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn is_unlocked(&self, skin: StylerSkin) -> bool {
+        match (skin.required_rank(), self.best_rank) {
+            (None, _) => true,
+            (Some(required), Some(best)) => best <= required,
+            (Some(_), None) => false,
+        }
+    }
+}
+
+/// Spends experience on an upgrade for the player's styler.
+#[derive(Event, Debug)]
+pub struct BuyUpgrade(pub Upgrade);
+
+/// Changes the look of the player's styler to a skin that has been unlocked.
+#[derive(Event, Debug)]
+pub struct ChooseSkin(pub StylerSkin);
+
+/// Experience given for a capture, per point it scored.
+const EXPERIENCE_PER_POINT: f32 = 0.1;
+
+fn earn_experience(scored: Trigger<CaptureScored>, mut progress: ResMut<StylerProgress>) {
+    let rank = scored.score.rank();
+    progress.experience += (scored.score.points() as f32 * EXPERIENCE_PER_POINT) as u32;
+
+    if progress.best_rank.is_none_or(|best| rank < best) {
+        let newly_unlocked = StylerSkin::ALL
+            .into_iter()
+            .filter(|skin| !progress.is_unlocked(*skin))
+            .filter(|skin| {
+                skin.required_rank()
+                    .is_some_and(|required| rank <= required)
+            })
+            .collect::<Vec<_>>();
+        progress.best_rank = Some(rank);
+        for skin in newly_unlocked {
+            info!("Unlocked the {skin:?} styler");
+        }
+    }
+}
+
+fn buy_upgrades(mut events: EventReader<BuyUpgrade>, mut progress: ResMut<StylerProgress>) {
+    for BuyUpgrade(upgrade) in events.read() {
+        if let Err(reason) = progress.can_buy(*upgrade) {
+            warn!("Couldn't buy upgrade: {reason}");
+            continue;
+        }
+
+        progress.experience -= upgrade.cost();
+        progress.upgrades.push(*upgrade);
+        info!("Bought the {upgrade:?} upgrade");
+    }
+}
+
+fn choose_skin(mut events: EventReader<ChooseSkin>, mut progress: ResMut<StylerProgress>) {
+    for ChooseSkin(skin) in events.read() {
+        if progress.is_unlocked(*skin) {
+            progress.skin = *skin;
+        } else {
+            warn!("The {skin:?} styler hasn't been unlocked yet");
+        }
+    }
+}
+
+/// Works the styler's stats out again from its base stats and every upgrade bought, whenever
+/// either changes or the player is given a new styler.
+fn apply_upgrades(
+    progress: Res<StylerProgress>,
+    config: Res<CaptureConfig>,
+    mut styler: Single<&mut Styler>,
+    mut energy: Single<&mut StylerEnergy>,
+) {
+    if !progress.is_changed() && !config.is_changed() && !styler.is_added() {
+        return;
+    }
+
+    let mut upgraded = Styler {
+        skin: progress.skin,
+        ..Styler::base(&config)
+    };
+    for upgrade in &progress.upgrades {
+        upgrade.apply(&mut upgraded);
+    }
+
+    energy.max = upgraded.energy;
+    energy.current = energy.current.min(energy.max);
+    styler.set_if_neq(upgraded);
+}
+
+/// The player's styler, or one without any upgrades if nothing holds one.
+pub(crate) fn styler_or_base(styler: Option<Single<&Styler>>, config: &CaptureConfig) -> Styler {
+    styler.map_or_else(|| Styler::base(config), |styler| styler.clone())
+}
@@ -0,0 +1,165 @@
+use crate::capture::styler::{BuyUpgrade, ChooseSkin, StylerProgress, StylerSkin, Upgrade};
+use crate::settings::{binding_just_pressed, rebinding_key};
+use crate::ui::{BUTTON_COLOR, FONT_SIZE, MENU_BACKGROUND_COLOR};
+use bevy::prelude::*;
+
+/// Lets the player spend experience on upgrades and choose between the skins they've unlocked.
+pub(crate) struct StylerMenuPlugin;
+impl Plugin for StylerMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<StylerMenu>()
+            .register_type::<ExperienceLabel>()
+            .register_type::<StylerButton>()
+            .add_systems(Startup, spawn_styler_menu)
+            .add_systems(
+                Update,
+                (
+                    toggle_styler_menu.run_if(
+                        binding_just_pressed(|bindings| bindings.styler_menu)
+                            .and(not(rebinding_key)),
+                    ),
+                    press_styler_buttons,
+                    label_styler_menu.run_if(resource_changed::<StylerProgress>),
+                )
+                    .chain(),
+            );
+    }
+}
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct StylerMenu;
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct ExperienceLabel;
+
+/// A button that buys an upgrade or chooses a skin, labelled with whether it can.
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+#[reflect(Component)]
+enum StylerButton {
+    Upgrade(Upgrade),
+    Skin(StylerSkin),
+}
+
+impl StylerButton {
+    fn label(self, progress: &StylerProgress) -> String {
+        match self {
+            StylerButton::Upgrade(upgrade) if progress.has(upgrade) => {
+                format!("{upgrade:?}: bought")
+            }
+            StylerButton::Upgrade(upgrade) => match upgrade.requires() {
+                Some(required) if !progress.has(required) => {
+                    format!("{upgrade:?}: needs {required:?}")
+                }
+                _ => format!("{upgrade:?}: {} exp", upgrade.cost()),
+            },
+            StylerButton::Skin(skin) if progress.skin == skin => format!("{skin:?}: chosen"),
+            StylerButton::Skin(skin) => match skin.required_rank() {
+                Some(rank) if !progress.is_unlocked(skin) => format!("{skin:?}: needs rank {rank}"),
+                _ => format!("{skin:?}"),
+            },
+        }
+    }
+}
+
+fn styler_button(button: StylerButton) -> impl Bundle {
+    (
+        Button,
+        button,
+        Node {
+            padding: UiRect::horizontal(Val::Px(4.)),
+            ..default()
+        },
+        BackgroundColor(BUTTON_COLOR),
+        children![(Text::default(), TextFont::from_font_size(FONT_SIZE))],
+    )
+}
+
+fn spawn_styler_menu(mut commands: Commands) {
+    commands
+        .spawn((
+            Name::from("Styler Menu"),
+            StylerMenu,
+            Node {
+                position_type: PositionType::Absolute,
+                align_self: AlignSelf::Center,
+                justify_self: JustifySelf::Center,
+                display: Display::Grid,
+                grid_template_columns: vec![GridTrack::auto(); 2],
+                column_gap: Val::Px(6.),
+                row_gap: Val::Px(4.),
+                padding: UiRect::all(Val::Px(12.)),
+                ..default()
+            },
+            BackgroundColor(MENU_BACKGROUND_COLOR),
+            GlobalZIndex(1),
+            Visibility::Hidden,
+        ))
+        .with_children(|menu| {
+            menu.spawn((Text::new("Styler"), TextFont::from_font_size(FONT_SIZE)));
+            menu.spawn((
+                ExperienceLabel,
+                Text::default(),
+                TextFont::from_font_size(FONT_SIZE),
+            ));
+
+            // Each upgrade sits beside a skin, for as long as there are skins left.
+            for index in 0..Upgrade::ALL.len().max(StylerSkin::ALL.len()) {
+                match Upgrade::ALL.get(index) {
+                    Some(upgrade) => menu.spawn(styler_button(StylerButton::Upgrade(*upgrade))),
+                    None => menu.spawn(Node::default()),
+                };
+                match StylerSkin::ALL.get(index) {
+                    Some(skin) => menu.spawn(styler_button(StylerButton::Skin(*skin))),
+                    None => menu.spawn(Node::default()),
+                };
+            }
+        });
+}
+
+fn toggle_styler_menu(menu: Single<&mut Visibility, With<StylerMenu>>) {
+    let mut visibility = menu.into_inner();
+    *visibility = match *visibility {
+        Visibility::Hidden => Visibility::Inherited,
+        _ => Visibility::Hidden,
+    };
+}
+
+fn press_styler_buttons(
+    mut buy_upgrade: EventWriter<BuyUpgrade>,
+    mut choose_skin: EventWriter<ChooseSkin>,
+    buttons: Query<(&Interaction, &StylerButton), Changed<Interaction>>,
+) {
+    for (interaction, button) in buttons {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        match *button {
+            StylerButton::Upgrade(upgrade) => {
+                buy_upgrade.write(BuyUpgrade(upgrade));
+            }
+            StylerButton::Skin(skin) => {
+                choose_skin.write(ChooseSkin(skin));
+            }
+        }
+    }
+}
+
+fn label_styler_menu(
+    progress: Res<StylerProgress>,
+    mut experience: Single<&mut Text, With<ExperienceLabel>>,
+    buttons: Query<(&StylerButton, &Children)>,
+    mut labels: Query<&mut Text, Without<ExperienceLabel>>,
+) {
+    experience.0 = format!("{} exp", progress.experience);
+
+    for (button, children) in buttons {
+        for child in children {
+            if let Ok(mut text) = labels.get_mut(*child) {
+                text.0 = button.label(&progress);
+            }
+        }
+    }
+}
@@ -0,0 +1,1169 @@
+//! Headless tests for the capture rules, driven by scripted strokes instead of a mouse.
+
+use crate::capture::assist::{Assist, AssistCooldown};
+use crate::capture::config::CaptureConfig;
+use crate::capture::input::CaptureInputPlugin;
+use crate::capture::power::{line_power, LoopShape};
+use crate::capture::record::{RecordedInput, Recorder, Recording, Replay};
+use crate::capture::score::{BestScores, CaptureScore, CaptureScored};
+use crate::capture::session::{
+    CaptureSession, CaptureSessionChanged, CapturedCreature, EndReason, SessionState,
+};
+use crate::capture::styler::menu::StylerMenuPlugin;
+use crate::capture::styler::{BuyUpgrade, Styler, StylerProgress, StylerSkin, Upgrade};
+use crate::capture::ui::{CaptureGauge, CaptureUiPlugin};
+use crate::capture::{
+    CancelReason, CaptureCancelled, CaptureCorePlugin, CaptureLine, CaptureLineConnected,
+    CaptureLogicPlugin, CapturePointLifted, CapturePointMoved, CapturePointPressed,
+    CaptureProgressChanged, Captured,
+};
+use crate::creature::roster::{Roster, RosterEntry, RosterPlugin};
+use crate::creature::types::CreatureType;
+use crate::creature::{CaptureDecay, CaptureProgress, CaptureRequirements, Creature, Species};
+use crate::movement::Speed;
+use crate::save::SaveFile;
+use crate::settings::{CursorLeavePolicy, Settings};
+use crate::DespawnPlugin;
+use avian2d::prelude::*;
+use bevy::ecs::event::EventCursor;
+use bevy::input::keyboard::{Key, KeyboardInput, NativeKey};
+use bevy::input::{ButtonState, InputPlugin};
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use bevy::window::{CursorEntered, CursorLeft, ExitCondition};
+use std::f32::consts::TAU;
+use std::time::Duration;
+
+/// How many points make up a single turn of a scripted stroke.
+const STEPS_PER_TURN: usize = 48;
+
+/// Every [`CaptureSessionChanged`] triggered since the harness last collected them.
+#[derive(Resource, Default)]
+struct SessionChanges(Vec<CaptureSessionChanged>);
+
+fn log_session_change(
+    changed: Trigger<CaptureSessionChanged>,
+    mut changes: ResMut<SessionChanges>,
+) {
+    changes.0.push(changed.event().clone());
+}
+
+/// Runs the capture rules without a window, advancing time by one fixed step per update.
+pub(crate) struct Harness {
+    pub(crate) app: App,
+    connected: EventCursor<CaptureLineConnected>,
+    pub(crate) connected_count: usize,
+    pub(crate) captured: Vec<Entity>,
+    pub(crate) failed: Vec<Entity>,
+
+    /// Every state any session has moved on to, in order.
+    pub(crate) session_states: Vec<SessionState>,
+}
+
+impl Harness {
+    pub(crate) fn new() -> Self {
+        Self::with(|_| {})
+    }
+
+    /// Like [`Harness::new`], with anything else the test needs added to the app before it starts.
+    pub(crate) fn with(setup: impl FnOnce(&mut App)) -> Self {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            TransformPlugin,
+            InputPlugin,
+            AssetPlugin::default(),
+            bevy::scene::ScenePlugin,
+            PhysicsPlugins::default(),
+            DespawnPlugin,
+            CaptureLogicPlugin,
+        ))
+        .init_resource::<Assets<Mesh>>()
+        .init_resource::<Settings>()
+        .add_event::<CursorLeft>();
+        setup(&mut app);
+
+        Self::start(app)
+    }
+
+    /// Runs [`CaptureCorePlugin`] with nothing but physics, the way another game would embed it.
+    pub(crate) fn core() -> Self {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            InputPlugin,
+            WindowPlugin {
+                primary_window: None,
+                exit_condition: ExitCondition::DontExit,
+                ..default()
+            },
+            TransformPlugin,
+            bevy::scene::ScenePlugin,
+            PhysicsPlugins::default(),
+            CaptureCorePlugin::default(),
+        ))
+        .init_asset::<Image>()
+        .init_asset::<Mesh>()
+        .init_asset::<ColorMaterial>()
+        .init_asset::<Font>();
+
+        Self::start(app)
+    }
+
+    fn start(mut app: App) -> Self {
+        app.init_resource::<SessionChanges>()
+            .add_observer(log_session_change)
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(
+                1. / 60.,
+            )));
+
+        app.finish();
+        app.update();
+
+        Self {
+            app,
+            connected: default(),
+            connected_count: 0,
+            captured: vec![],
+            failed: vec![],
+            session_states: vec![],
+        }
+    }
+
+    pub(crate) fn spawn_creature(&mut self, position: Vec2, requirements: u32) -> Entity {
+        self.app
+            .world_mut()
+            .spawn((
+                Creature,
+                CaptureProgress::default(),
+                CaptureRequirements(requirements),
+                Transform::from_translation(position.extend(0.)),
+            ))
+            .id()
+    }
+
+    pub(crate) fn progress(&self, creature: Entity) -> u32 {
+        self.app
+            .world()
+            .get::<CaptureProgress>(creature)
+            .expect("Creature should have capture progress")
+            .progress
+    }
+
+    /// Gives the harness a styler to hold, as the player does, so that it takes upgrades.
+    pub(crate) fn hold_styler(&mut self) {
+        self.app
+            .world_mut()
+            .spawn(Styler::base(&CaptureConfig::default()));
+        self.update();
+    }
+
+    /// How many capture lines there are, which is none once every session has ended.
+    pub(crate) fn line_count(&mut self) -> usize {
+        let world = self.app.world_mut();
+        world.query::<&CaptureLine>().iter(world).count()
+    }
+
+    pub(crate) fn is_captured(&self, creature: Entity) -> bool {
+        self.app.world().get::<Captured>(creature).is_some()
+    }
+
+    /// Runs a single step, then collects any capture events that were sent during it.
+    pub(crate) fn update(&mut self) {
+        self.app.update();
+
+        self.connected_count += self
+            .connected
+            .read(self.app.world().resource::<Events<CaptureLineConnected>>())
+            .count();
+
+        let changes = std::mem::take(&mut self.app.world_mut().resource_mut::<SessionChanges>().0);
+        for changed in changes {
+            match changed.ended() {
+                Some(EndReason::Lifted { failed }) => self.failed.extend(failed),
+                Some(EndReason::Success { captured, failed }) => {
+                    self.captured
+                        .extend(captured.iter().map(|captured| captured.creature));
+                    self.failed.extend(failed);
+                }
+                _ => {}
+            }
+            self.session_states.push(changed.state);
+        }
+    }
+
+    pub(crate) fn press(&mut self, point: Vec2) {
+        self.app.world_mut().send_event(CapturePointPressed(point));
+        self.update();
+    }
+
+    pub(crate) fn move_to(&mut self, point: Vec2) {
+        self.app.world_mut().send_event(CapturePointMoved(point));
+        self.update();
+    }
+
+    pub(crate) fn lift(&mut self) {
+        self.app.world_mut().send_event(CapturePointLifted);
+        self.update();
+    }
+
+    /// Presses and holds a key for a single step, letting go of it in the next.
+    pub(crate) fn press_key(&mut self, key_code: KeyCode) {
+        let key = |state| KeyboardInput {
+            key_code,
+            logical_key: Key::Unidentified(NativeKey::Unidentified),
+            state,
+            text: None,
+            repeat: false,
+            window: Entity::PLACEHOLDER,
+        };
+        self.app.world_mut().send_event(key(ButtonState::Pressed));
+        self.update();
+        self.app.world_mut().send_event(key(ButtonState::Released));
+    }
+
+    pub(crate) fn cancel(&mut self, reason: CancelReason) {
+        self.app.world_mut().send_event(CaptureCancelled(reason));
+        self.update();
+    }
+
+    /// The most recently finished capture session.
+    pub(crate) fn recording(&self) -> Recording {
+        self.app
+            .world()
+            .resource::<Recorder>()
+            .last
+            .clone()
+            .expect("A capture session should have been recorded")
+    }
+
+    /// Plays a recording back through the capture rules, returning the creatures spawned for it.
+    pub(crate) fn replay(&mut self, recording: Recording) -> Vec<Entity> {
+        self.app.insert_resource(Replay::new(recording));
+        while !self.app.world().resource::<Replay>().is_finished() {
+            self.update();
+        }
+
+        self.app
+            .world_mut()
+            .remove_resource::<Replay>()
+            .map(|replay| replay.creatures)
+            .unwrap_or_default()
+    }
+
+    /// Presses at the first point of the stroke and moves through the rest, one point per step.
+    pub(crate) fn stroke(&mut self, points: &[Vec2]) {
+        let Some((first, rest)) = points.split_first() else {
+            return;
+        };
+
+        self.press(*first);
+        for point in rest {
+            self.move_to(*point);
+        }
+    }
+}
+
+/// A stroke that goes around `center` the given number of times, counter-clockwise.
+pub(crate) fn circle(center: Vec2, radius: f32, turns: f32) -> Vec<Vec2> {
+    let steps = (STEPS_PER_TURN as f32 * turns) as usize;
+    (0..=steps)
+        .map(|step| {
+            let angle = step as f32 / STEPS_PER_TURN as f32 * TAU;
+            center + Vec2::from_angle(angle) * radius
+        })
+        .collect()
+}
+
+/// A stroke that goes around `center` like [`circle`], moving from one radius to the other.
+pub(crate) fn spiral(center: Vec2, from: f32, to: f32, turns: f32) -> Vec<Vec2> {
+    let steps = (STEPS_PER_TURN as f32 * turns) as usize;
+    (0..=steps)
+        .map(|step| {
+            let turned = step as f32 / STEPS_PER_TURN as f32;
+            let radius = from + (to - from) * turned / turns;
+            center + Vec2::from_angle(turned * TAU) * radius
+        })
+        .collect()
+}
+
+/// A stroke that crosses itself at `center`, with one lobe to the right and one to the left.
+pub(crate) fn figure_eight(center: Vec2, radius: f32) -> Vec<Vec2> {
+    // Starting half a step early makes the stroke cross through the centre rather than touching
+    // its own first point.
+    let step = TAU / STEPS_PER_TURN as f32;
+    (0..=STEPS_PER_TURN + 1)
+        .map(|i| {
+            let t = i as f32 * step - step / 2.;
+            center + Vec2::new(t.sin(), t.sin() * t.cos()) * radius
+        })
+        .collect()
+}
+
+#[test]
+fn core_plugin_captures_on_its_own() {
+    let mut harness = Harness::core();
+    let first = harness.spawn_creature(Vec2::ZERO, 1);
+    let second = harness.spawn_creature(Vec2::new(300., 0.), 1);
+
+    harness.stroke(&circle(Vec2::ZERO, 60., 1.1));
+    harness.lift();
+    harness.stroke(&circle(Vec2::new(300., 0.), 60., 1.1));
+    harness.lift();
+
+    assert_eq!(harness.captured, vec![first, second]);
+    assert_eq!(harness.line_count(), 0);
+}
+
+#[test]
+fn circle_around_creature_adds_progress() {
+    let mut harness = Harness::new();
+    let creature = harness.spawn_creature(Vec2::ZERO, 3);
+
+    harness.stroke(&circle(Vec2::ZERO, 60., 1.1));
+
+    assert_eq!(harness.connected_count, 1);
+    assert_eq!(harness.progress(creature), 1);
+}
+
+#[test]
+fn circle_away_from_creature_adds_nothing() {
+    let mut harness = Harness::new();
+    let creature = harness.spawn_creature(Vec2::new(200., 0.), 3);
+
+    harness.stroke(&circle(Vec2::ZERO, 60., 1.1));
+
+    assert_eq!(harness.connected_count, 1);
+    assert_eq!(harness.progress(creature), 0);
+}
+
+#[test]
+fn enough_loops_capture_creature() {
+    let mut harness = Harness::new();
+    let creature = harness.spawn_creature(Vec2::ZERO, 3);
+
+    harness.stroke(&circle(Vec2::ZERO, 60., 3.1));
+    harness.lift();
+
+    assert_eq!(harness.progress(creature), 3);
+    assert_eq!(harness.captured, vec![creature]);
+    assert!(harness.is_captured(creature));
+}
+
+#[test]
+fn lifting_too_early_fails_capture() {
+    let mut harness = Harness::new();
+    let creature = harness.spawn_creature(Vec2::ZERO, 3);
+
+    harness.stroke(&circle(Vec2::ZERO, 60., 1.1));
+    harness.lift();
+
+    assert!(harness.captured.is_empty());
+    assert_eq!(harness.failed, vec![creature]);
+    assert!(!harness.is_captured(creature));
+}
+
+#[test]
+fn figure_eight_loops_first_lobe() {
+    let mut harness = Harness::new();
+    let right = harness.spawn_creature(Vec2::new(30., 0.), 3);
+    let left = harness.spawn_creature(Vec2::new(-30., 0.), 3);
+
+    harness.stroke(&figure_eight(Vec2::ZERO, 60.));
+
+    // Closing the first lobe truncates the line back to before the crossing, so the second lobe
+    // has nothing left to cross and stays open. The lobe is tighter than a plain circle, so it
+    // counts for more.
+    assert_eq!(harness.connected_count, 1);
+    assert_eq!(harness.progress(right), 2);
+    assert_eq!(harness.progress(left), 0);
+}
+
+#[test]
+fn near_miss_does_not_connect() {
+    let mut harness = Harness::new();
+    let creature = harness.spawn_creature(Vec2::ZERO, 3);
+
+    // Passes inside its own start, closer than the width of the line, without ever crossing it.
+    let width = CaptureConfig::default().line_width;
+    harness.stroke(&spiral(Vec2::ZERO, 60., 60. - width * 0.6, 1.2));
+
+    assert_eq!(harness.connected_count, 0);
+    assert_eq!(harness.progress(creature), 0);
+}
+
+#[test]
+fn recording_survives_encoding() {
+    let mut harness = Harness::new();
+    harness.spawn_creature(Vec2::new(10., -5.), 3);
+    harness.stroke(&figure_eight(Vec2::ZERO, 60.));
+    harness.lift();
+
+    let recording = harness.recording();
+    let mut bytes = vec![];
+    recording.encode(&mut bytes).unwrap();
+
+    assert_eq!(Recording::decode(&mut bytes.as_slice()).unwrap(), recording);
+}
+
+#[test]
+fn recording_keeps_creature_and_styler() {
+    let mut recorded = Harness::new();
+    let creature = recorded.spawn_creature(Vec2::ZERO, 3);
+    recorded.app.world_mut().entity_mut(creature).insert((
+        CreatureType::Fire,
+        Collider::circle(10.),
+        CaptureDecay::new(Duration::from_secs(1), Duration::from_millis(250)),
+    ));
+    *recorded.app.world_mut().resource_mut::<StylerProgress>() = StylerProgress {
+        upgrades: vec![Upgrade::Power],
+        skin: StylerSkin::Tide,
+        ..default()
+    };
+    recorded.hold_styler();
+    recorded.stroke(&circle(Vec2::ZERO, 60., 1.1));
+    recorded.lift();
+
+    let mut recording = recorded.recording();
+    assert_eq!(
+        recording.styler,
+        Some(Styler {
+            power: 2,
+            skin: StylerSkin::Tide,
+            ..Styler::base(&CaptureConfig::default())
+        })
+    );
+
+    // Longer than fits in a u32 of nanoseconds.
+    recording.frames[0].delta = Duration::from_secs(5);
+    let mut bytes = vec![];
+    recording.encode(&mut bytes).unwrap();
+    let recording = Recording::decode(&mut bytes.as_slice()).unwrap();
+    assert_eq!(recording.frames[0].delta, Duration::from_secs(5));
+
+    let mut replayed = Harness::new();
+    replayed.hold_styler();
+    let own_styler = replayed
+        .app
+        .world_mut()
+        .query::<&Styler>()
+        .single(replayed.app.world())
+        .unwrap()
+        .clone();
+    let creatures = replayed.replay(recording);
+
+    let world = replayed.app.world();
+    assert_eq!(
+        world.get::<CreatureType>(creatures[0]),
+        Some(&CreatureType::Fire)
+    );
+    assert_eq!(
+        world
+            .get::<Collider>(creatures[0])
+            .and_then(|collider| collider.shape().as_ball())
+            .map(|ball| ball.radius),
+        Some(10.)
+    );
+    assert_eq!(
+        world
+            .get::<CaptureDecay>(creatures[0])
+            .map(|decay| decay.delay),
+        Some(Duration::from_secs(1))
+    );
+    assert_eq!(
+        replayed
+            .app
+            .world_mut()
+            .query::<&Styler>()
+            .single(replayed.app.world())
+            .unwrap(),
+        &own_styler
+    );
+}
+
+#[test]
+fn replay_reproduces_capture() {
+    let mut recorded = Harness::new();
+    let creature = recorded.spawn_creature(Vec2::ZERO, 3);
+    recorded.stroke(&circle(Vec2::ZERO, 60., 3.1));
+    recorded.lift();
+
+    let mut replayed = Harness::new();
+    let creatures = replayed.replay(recorded.recording());
+
+    assert_eq!(creatures.len(), 1);
+    assert_eq!(replayed.connected_count, recorded.connected_count);
+    assert_eq!(replayed.progress(creatures[0]), recorded.progress(creature));
+    assert_eq!(replayed.captured, creatures);
+}
+
+#[test]
+fn replay_brings_its_own_styler_when_none_is_held() {
+    let mut recorded = Harness::new();
+    recorded
+        .app
+        .world_mut()
+        .resource_mut::<StylerProgress>()
+        .upgrades = vec![Upgrade::Power];
+    recorded.hold_styler();
+    let creature = recorded.spawn_creature(Vec2::ZERO, 5);
+    recorded.stroke(&circle(Vec2::ZERO, 60., 1.1));
+    recorded.lift();
+
+    let mut replayed = Harness::new();
+    let creatures = replayed.replay(recorded.recording());
+    replayed.update();
+
+    assert_eq!(recorded.progress(creature), 2);
+    assert_eq!(replayed.progress(creatures[0]), 2);
+    let world = replayed.app.world_mut();
+    assert_eq!(world.query::<&Styler>().iter(world).count(), 0);
+}
+
+#[test]
+fn replay_reproduces_figure_eight() {
+    let mut recorded = Harness::new();
+    recorded.spawn_creature(Vec2::new(30., 0.), 3);
+    recorded.spawn_creature(Vec2::new(-30., 0.), 3);
+    recorded.stroke(&figure_eight(Vec2::ZERO, 60.));
+    recorded.lift();
+
+    let mut replayed = Harness::new();
+    replayed.replay(recorded.recording());
+
+    assert_eq!(replayed.connected_count, recorded.connected_count);
+    assert_eq!(replayed.failed.len(), recorded.failed.len());
+}
+
+#[test]
+fn replay_reproduces_assist() {
+    let burst = Assist::ProgressBurst {
+        radius: 100.,
+        amount: 1,
+    };
+    let mut recorded = with_partner(burst.clone());
+    let creature = recorded.spawn_creature(Vec2::ZERO, 5);
+    recorded.stroke(&circle(Vec2::ZERO, 60., 1.1));
+    recorded.press_key(KeyCode::Space);
+    recorded.lift();
+    assert_eq!(recorded.progress(creature), 2);
+
+    let recording = recorded.recording();
+    assert!(recording
+        .frames
+        .iter()
+        .any(|frame| frame.inputs.contains(&RecordedInput::Assist(burst.clone()))));
+    let mut bytes = vec![];
+    recording.encode(&mut bytes).unwrap();
+    let recording = Recording::decode(&mut bytes.as_slice()).unwrap();
+
+    // Without a partner of its own, the recorded assist is all there is.
+    let mut replayed = Harness::new();
+    let creatures = replayed.replay(recording);
+
+    assert_eq!(replayed.progress(creatures[0]), 2);
+}
+
+#[test]
+fn replay_keeps_time_scale() {
+    let mut recorded = Harness::new();
+    let creature = recorded.spawn_creature(Vec2::ZERO, 5);
+    recorded
+        .app
+        .world_mut()
+        .entity_mut(creature)
+        .insert(CaptureDecay::new(
+            Duration::from_millis(100),
+            Duration::from_millis(50),
+        ));
+    recorded
+        .app
+        .world_mut()
+        .resource_mut::<Time<Virtual>>()
+        .set_relative_speed(0.25);
+    recorded.stroke(&circle(Vec2::ZERO, 60., 1.1));
+    for _ in 0..12 {
+        recorded.update();
+    }
+    recorded.lift();
+    assert!(recorded.progress(creature) > 0);
+
+    let mut replayed = Harness::new();
+    let creatures = replayed.replay(recorded.recording());
+
+    assert_eq!(replayed.progress(creatures[0]), recorded.progress(creature));
+    assert_eq!(
+        replayed
+            .app
+            .world()
+            .resource::<Time<Virtual>>()
+            .relative_speed(),
+        1.
+    );
+}
+
+#[test]
+fn replay_sets_live_creatures_aside() {
+    let mut recorded = Harness::new();
+    recorded.spawn_creature(Vec2::ZERO, 3);
+    recorded.stroke(&circle(Vec2::ZERO, 60., 3.1));
+    recorded.lift();
+
+    let mut replayed = Harness::new();
+    let live = replayed.spawn_creature(Vec2::ZERO, 3);
+    let creatures = replayed.replay(recorded.recording());
+    replayed.update();
+
+    assert_eq!(replayed.captured, creatures);
+    assert!(replayed.app.world().get::<Captured>(live).is_none());
+    assert_eq!(replayed.progress(live), 0);
+}
+
+/// A harness that also runs the cursor handling of the input plugin, with the given policy for the
+/// cursor leaving the window.
+fn with_cursor_policy(policy: CursorLeavePolicy) -> Harness {
+    Harness::with(|app| {
+        app.add_plugins(CaptureInputPlugin)
+            .add_event::<CursorEntered>()
+            .add_event::<CursorMoved>()
+            .insert_resource(Settings {
+                cursor_leave: policy,
+                ..default()
+            });
+    })
+}
+
+impl Harness {
+    pub(crate) fn leave_window(&mut self) {
+        self.app.world_mut().send_event(CursorLeft {
+            window: Entity::PLACEHOLDER,
+        });
+        self.update();
+    }
+
+    pub(crate) fn enter_window(&mut self) {
+        self.app.world_mut().send_event(CursorEntered {
+            window: Entity::PLACEHOLDER,
+        });
+        self.update();
+    }
+
+    fn line_start(&mut self) -> Option<Vec2> {
+        self.app
+            .world_mut()
+            .query::<&CaptureLine>()
+            .single(self.app.world())
+            .ok()
+            .and_then(|line| line.line.first().copied())
+    }
+}
+
+#[test]
+fn clamping_keeps_line_when_cursor_leaves() {
+    let mut harness = with_cursor_policy(CursorLeavePolicy::Clamp);
+    let creature = harness.spawn_creature(Vec2::ZERO, 3);
+    let stroke = circle(Vec2::ZERO, 60., 1.1);
+    let (first_half, second_half) = stroke.split_at(stroke.len() / 2);
+
+    harness.stroke(first_half);
+    harness.leave_window();
+    for point in second_half {
+        harness.move_to(*point);
+    }
+    harness.enter_window();
+
+    assert_eq!(harness.connected_count, 1);
+    assert_eq!(harness.progress(creature), 1);
+}
+
+#[test]
+fn cancelling_when_cursor_leaves_ends_session() {
+    let mut harness = with_cursor_policy(CursorLeavePolicy::Cancel);
+    let creature = harness.spawn_creature(Vec2::ZERO, 3);
+
+    harness.stroke(&circle(Vec2::ZERO, 60., 1.1));
+    harness.leave_window();
+    harness.enter_window();
+
+    assert_eq!(harness.progress(creature), 0);
+    assert_eq!(harness.line_count(), 0);
+    assert_eq!(
+        harness.session_states.last(),
+        Some(&SessionState::Ended {
+            reason: EndReason::LeftWindow
+        })
+    );
+    assert_eq!(
+        harness.recording().frames.last().unwrap().inputs,
+        vec![RecordedInput::Cancelled(CancelReason::CursorLeft)]
+    );
+}
+
+#[test]
+fn waiting_restarts_line_where_cursor_returns() {
+    let mut harness = with_cursor_policy(CursorLeavePolicy::Wait);
+    let creature = harness.spawn_creature(Vec2::ZERO, 3);
+    let stroke = circle(Vec2::ZERO, 60., 1.1);
+    let (first_half, second_half) = stroke.split_at(stroke.len() / 2);
+
+    harness.stroke(first_half);
+    harness.leave_window();
+    harness.enter_window();
+    for point in second_half {
+        harness.move_to(*point);
+    }
+
+    // Without a straight segment from where the cursor left, the second half can't close a loop.
+    assert_eq!(harness.connected_count, 0);
+    assert_eq!(harness.progress(creature), 0);
+    assert_eq!(harness.line_start(), Some(second_half[0]));
+
+    harness.lift();
+    let recording = harness.recording();
+    assert!(recording
+        .frames
+        .iter()
+        .any(|frame| frame.inputs.contains(&RecordedInput::Restarted)));
+
+    let mut bytes = vec![];
+    recording.encode(&mut bytes).unwrap();
+    assert_eq!(Recording::decode(&mut bytes.as_slice()).unwrap(), recording);
+}
+
+#[test]
+fn waiting_too_long_cancels() {
+    let mut harness = with_cursor_policy(CursorLeavePolicy::Wait);
+    harness.spawn_creature(Vec2::ZERO, 3);
+
+    harness.stroke(&circle(Vec2::ZERO, 60., 0.5));
+    harness.leave_window();
+    let grace = Duration::from_millis(Settings::default().cursor_grace_ms);
+    for _ in 0..(grace.as_secs_f32() * 60.).ceil() as usize + 1 {
+        harness.update();
+    }
+
+    assert_eq!(harness.line_count(), 0);
+    assert_eq!(
+        harness.session_states.last(),
+        Some(&SessionState::Ended {
+            reason: EndReason::LeftWindow
+        })
+    );
+    assert_eq!(
+        harness.recording().frames.last().unwrap().inputs,
+        vec![RecordedInput::Cancelled(CancelReason::GracePeriodExpired)]
+    );
+}
+
+#[test]
+fn cancelling_resets_progress_without_failing() {
+    let mut harness = Harness::new();
+    let creature = harness.spawn_creature(Vec2::ZERO, 3);
+
+    harness.stroke(&circle(Vec2::ZERO, 60., 1.1));
+    harness.cancel(CancelReason::GracePeriodExpired);
+
+    assert_eq!(harness.progress(creature), 0);
+    assert!(harness.failed.is_empty());
+    assert_eq!(
+        harness.recording().frames.last().unwrap().inputs,
+        vec![RecordedInput::Cancelled(CancelReason::GracePeriodExpired)]
+    );
+}
+
+#[test]
+fn session_goes_through_lifecycle() {
+    let mut harness = Harness::new();
+    let creature = harness.spawn_creature(Vec2::ZERO, 3);
+
+    harness.stroke(&circle(Vec2::ZERO, 60., 3.1));
+    harness.lift();
+
+    let mut states = harness.session_states.clone();
+    states.dedup();
+    assert_eq!(
+        states,
+        vec![
+            SessionState::Started,
+            SessionState::Drawing,
+            SessionState::LoopClosed,
+            SessionState::Drawing,
+            SessionState::LoopClosed,
+            SessionState::Drawing,
+            SessionState::LoopClosed,
+            SessionState::Drawing,
+            SessionState::Ended {
+                reason: EndReason::Success {
+                    captured: vec![CapturedCreature {
+                        creature,
+                        overshot_by: 0,
+                    }],
+                    failed: vec![],
+                }
+            },
+        ]
+    );
+}
+
+#[test]
+fn session_ends_once() {
+    let mut harness = Harness::new();
+    harness.spawn_creature(Vec2::ZERO, 3);
+
+    harness.stroke(&circle(Vec2::ZERO, 60., 1.1));
+    harness.cancel(CancelReason::CursorLeft);
+    harness.lift();
+
+    assert_eq!(
+        harness.session_states.last(),
+        Some(&SessionState::Ended {
+            reason: EndReason::LeftWindow
+        })
+    );
+    assert!(harness.failed.is_empty());
+}
+
+#[test]
+fn upgraded_styler_adds_more_progress_per_loop() {
+    let mut harness = Harness::new();
+    harness.hold_styler();
+    let creature = harness.spawn_creature(Vec2::ZERO, 3);
+    harness
+        .app
+        .world_mut()
+        .resource_mut::<StylerProgress>()
+        .experience = Upgrade::Power.cost();
+    harness
+        .app
+        .world_mut()
+        .send_event(BuyUpgrade(Upgrade::Overcharge));
+    harness
+        .app
+        .world_mut()
+        .send_event(BuyUpgrade(Upgrade::Power));
+    harness.update();
+
+    harness.stroke(&circle(Vec2::ZERO, 60., 1.1));
+
+    // Overcharge needs Power first, so only Power was bought.
+    assert_eq!(
+        harness.app.world().resource::<StylerProgress>().upgrades,
+        vec![Upgrade::Power]
+    );
+    assert_eq!(harness.progress(creature), 2);
+}
+
+#[test]
+fn styler_menu_buys_upgrades() {
+    let mut harness = Harness::with(|app| {
+        app.add_plugins(StylerMenuPlugin);
+    });
+    harness
+        .app
+        .world_mut()
+        .resource_mut::<StylerProgress>()
+        .experience = Upgrade::LongLine.cost();
+    harness.update();
+
+    let label = |harness: &mut Harness, label: &str| {
+        let world = harness.app.world_mut();
+        world
+            .query::<(&Text, &ChildOf)>()
+            .iter(world)
+            .find(|(text, _)| text.0 == label)
+            .map(|(_, button)| button.parent())
+    };
+    let button = label(&mut harness, "LongLine: 50 exp").unwrap();
+    harness
+        .app
+        .world_mut()
+        .entity_mut(button)
+        .insert(Interaction::Pressed);
+    harness.update();
+    harness.update();
+
+    assert_eq!(
+        harness.app.world().resource::<StylerProgress>().upgrades,
+        vec![Upgrade::LongLine]
+    );
+    assert_eq!(label(&mut harness, "LongLine: bought"), Some(button));
+}
+
+#[test]
+fn drawing_up_to_a_loop_does_not_slow_it_down() {
+    // A quick loop, drawn in a fraction of a second.
+    let fast_loop = circle(Vec2::ZERO, 30., 1.1)
+        .into_iter()
+        .step_by(4)
+        .collect::<Vec<_>>();
+    let mut direct = Harness::new();
+    let creature = direct.spawn_creature(Vec2::ZERO, 10);
+    direct.stroke(&fast_loop);
+
+    let lead_in = (0..=120)
+        .rev()
+        .map(|step| Vec2::new(30. + step as f32 * 2., 0.))
+        .collect::<Vec<_>>();
+    let mut led_in = Harness::new();
+    let led_in_creature = led_in.spawn_creature(Vec2::ZERO, 10);
+    led_in.stroke(&[lead_in, fast_loop].concat());
+
+    assert!(direct.progress(creature) > 1);
+    assert_eq!(led_in.progress(led_in_creature), direct.progress(creature));
+}
+
+#[test]
+fn tight_fast_loops_have_more_power() {
+    let styler = Styler::base(&CaptureConfig::default());
+    let tight = LoopShape::of(&circle(Vec2::ZERO, 30., 1.), Duration::from_secs_f32(0.2));
+    let lazy = LoopShape::of(&circle(Vec2::ZERO, 200., 1.), Duration::from_secs(5));
+
+    assert_eq!(line_power(&tight, &styler, None), 4);
+    assert_eq!(line_power(&lazy, &styler, None), 1);
+}
+
+#[test]
+fn type_matchups_scale_line_power() {
+    let styler = Styler {
+        skin: StylerSkin::Ember,
+        ..Styler::base(&CaptureConfig::default())
+    };
+    let tight = LoopShape::of(&circle(Vec2::ZERO, 30., 1.), Duration::from_secs_f32(0.2));
+
+    assert_eq!(line_power(&tight, &styler, Some(CreatureType::Grass)), 6);
+    assert_eq!(line_power(&tight, &styler, Some(CreatureType::Water)), 3);
+    assert_eq!(line_power(&tight, &styler, Some(CreatureType::Normal)), 4);
+}
+
+#[test]
+fn gauge_is_found_among_other_children() {
+    let mut harness = Harness::with(|app| {
+        app.init_resource::<Assets<ColorMaterial>>()
+            .add_plugins(CaptureUiPlugin);
+    });
+    let creature = harness.spawn_creature(Vec2::ZERO, 3);
+    harness
+        .app
+        .world_mut()
+        .spawn((Name::from("Shadow"), ChildOf(creature)));
+
+    harness.stroke(&circle(Vec2::ZERO, 60., 2.1));
+
+    let mut gauges = harness.app.world_mut().query::<(&CaptureGauge, &ChildOf)>();
+    let gauges = gauges.iter(harness.app.world()).collect::<Vec<_>>();
+    assert_eq!(gauges.len(), 1);
+    assert_eq!(gauges[0].1.parent(), creature);
+    assert_eq!(gauges[0].0.fraction, 2. / 3.);
+}
+
+/// A harness whose partner slows every creature to a stop for a tenth of a second.
+fn with_partner(assist: Assist) -> Harness {
+    Harness::with(|app| {
+        app.insert_resource(Roster {
+            creatures: vec![RosterEntry {
+                species: Species("Testmon".into()),
+                nickname: "Testmon".into(),
+                assist: Some(assist),
+                field_moves: vec![],
+            }],
+            partner: Some(0),
+        });
+    })
+}
+
+fn with_slowing_partner() -> Harness {
+    with_partner(Assist::Slow {
+        factor: 0.,
+        seconds: 0.1,
+    })
+}
+
+#[test]
+fn slowing_to_a_stop_restores_speed() {
+    let mut harness = with_slowing_partner();
+    let creature = harness.spawn_creature(Vec2::new(200., 0.), 3);
+    harness
+        .app
+        .world_mut()
+        .entity_mut(creature)
+        .insert(Speed(50.));
+    let speed = |harness: &Harness| harness.app.world().get::<Speed>(creature).unwrap().0;
+
+    harness.press(Vec2::ZERO);
+    harness.press_key(KeyCode::Space);
+    assert_eq!(speed(&harness), 0.);
+
+    for _ in 0..10 {
+        harness.update();
+    }
+    assert_eq!(speed(&harness), 50.);
+}
+
+#[test]
+fn partner_key_switches_through_the_roster() {
+    let entry = |name: &str| RosterEntry {
+        species: Species(name.into()),
+        nickname: name.into(),
+        assist: None,
+        field_moves: vec![],
+    };
+    let mut harness = Harness::with(|app| {
+        app.add_plugins(RosterPlugin);
+    });
+    harness.app.world_mut().resource_mut::<Roster>().creatures =
+        vec![entry("Testmon"), entry("Othermon")];
+    let partner = |harness: &Harness| harness.app.world().resource::<Roster>().partner;
+
+    let mut partners = vec![];
+    for _ in 0..4 {
+        harness.press_key(KeyCode::Tab);
+        partners.push(partner(&harness));
+        harness.update();
+    }
+
+    assert_eq!(partners, [Some(0), Some(1), None, Some(0)]);
+}
+
+#[test]
+fn assists_are_not_used_while_paused() {
+    let mut harness = with_slowing_partner();
+    let creature = harness.spawn_creature(Vec2::new(200., 0.), 3);
+    harness
+        .app
+        .world_mut()
+        .entity_mut(creature)
+        .insert(Speed(50.));
+
+    harness.press(Vec2::ZERO);
+    harness
+        .app
+        .world_mut()
+        .resource_mut::<Time<Virtual>>()
+        .pause();
+    harness.press_key(KeyCode::Space);
+
+    assert_eq!(harness.app.world().get::<Speed>(creature).unwrap().0, 50.);
+    assert!(harness.app.world().resource::<AssistCooldown>().finished());
+}
+
+#[test]
+fn creature_shakes_when_it_breaks_free() {
+    let mut harness = Harness::new();
+    let creature = harness.spawn_creature(Vec2::ZERO, 5);
+    harness
+        .app
+        .world_mut()
+        .entity_mut(creature)
+        .insert(CaptureDecay::new(
+            Duration::from_millis(100),
+            Duration::from_millis(50),
+        ));
+    harness.stroke(&circle(Vec2::ZERO, 60., 1.1));
+    harness.lift();
+    assert!(harness.progress(creature) > 0);
+
+    while harness.progress(creature) > 0 {
+        harness.update();
+    }
+    harness.update();
+
+    let scale = harness
+        .app
+        .world()
+        .get::<Transform>(creature)
+        .unwrap()
+        .scale;
+    assert_ne!(scale, Vec3::ONE);
+}
+
+#[test]
+fn failed_sessions_do_not_count_against_the_next_capture() {
+    #[derive(Resource, Default)]
+    struct Scores(Vec<CaptureScore>);
+
+    let mut harness = Harness::with(|app| {
+        app.init_resource::<Scores>().add_observer(
+            |scored: Trigger<CaptureScored>, mut scores: ResMut<Scores>| {
+                scores.0.push(scored.score.clone());
+            },
+        );
+    });
+    let creature = harness.spawn_creature(Vec2::ZERO, 1);
+
+    harness.stroke(&circle(Vec2::new(300., 0.), 60., 1.1));
+    for _ in 0..120 {
+        harness.update();
+    }
+    harness.lift();
+    assert!(harness.captured.is_empty());
+
+    harness.stroke(&circle(Vec2::ZERO, 60., 1.1));
+    harness.lift();
+    assert_eq!(harness.captured, [creature]);
+
+    let scores = &harness.app.world().resource::<Scores>().0;
+    assert_eq!(scores.len(), 1);
+    assert_eq!(scores[0].loops, 1);
+    assert!(scores[0].time < Duration::from_secs(1));
+    assert!(scores[0].line_length < 500.);
+}
+
+#[test]
+fn line_events_name_their_session() {
+    #[derive(Resource, Default)]
+    struct Sessions(Vec<Option<Entity>>);
+
+    let mut harness = Harness::with(|app| {
+        app.init_resource::<Sessions>()
+            .add_observer(
+                |changed: Trigger<CaptureProgressChanged>, mut sessions: ResMut<Sessions>| {
+                    sessions.0.push(changed.session);
+                },
+            )
+            .add_systems(
+                Update,
+                |mut connected: EventReader<CaptureLineConnected>,
+                 mut sessions: ResMut<Sessions>| {
+                    sessions
+                        .0
+                        .extend(connected.read().map(|connected| Some(connected.session)));
+                },
+            );
+    });
+    harness.spawn_creature(Vec2::ZERO, 5);
+    harness.stroke(&circle(Vec2::ZERO, 60., 1.1));
+
+    let world = harness.app.world_mut();
+    let session = world
+        .query_filtered::<Entity, With<CaptureSession>>()
+        .single(world)
+        .unwrap();
+    let sessions = &world.resource::<Sessions>().0;
+    assert!(sessions.len() >= 2);
+    assert!(sessions.iter().all(|named| *named == Some(session)));
+}
+
+#[test]
+fn best_scores_round_trip_through_their_save_file() {
+    let mut harness = Harness::new();
+    let creature = harness.spawn_creature(Vec2::ZERO, 1);
+    harness
+        .app
+        .world_mut()
+        .entity_mut(creature)
+        .insert(Species("Testmon".into()));
+    harness.stroke(&circle(Vec2::ZERO, 60., 1.1));
+    harness.lift();
+    let scores = harness.app.world().resource::<BestScores>();
+    assert_eq!(scores.len(), 1);
+
+    let path = std::env::temp_dir()
+        .join(format!("bevymon_ranger_scores_{}", std::process::id()))
+        .join(BestScores::FILE_NAME);
+    scores.write_to(&path).unwrap();
+    let read = BestScores::read_from(&path);
+    let _ = std::fs::remove_dir_all(path.parent().unwrap());
+
+    assert_eq!(*read.unwrap(), **scores);
+}
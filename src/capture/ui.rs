@@ -1,24 +1,114 @@
-use crate::capture::{CaptureLineCollision, CaptureProgressChanged};
-use crate::creature::{CaptureProgress, CaptureRequirements, Creature};
+use crate::capture::score::CaptureScored;
+use crate::capture::session::{CaptureSessionChanged, EndReason};
+use crate::capture::{CaptureLine, CaptureProgressChanged};
+use crate::creature::{CaptureDecay, CaptureProgress, CaptureRequirements, Creature};
+use crate::settings::{binding_just_pressed, Settings};
 use crate::Despawn;
-use bevy::color;
+use bevy::asset::RenderAssetUsages;
 use bevy::prelude::*;
+use bevy::render::mesh::PrimitiveTopology;
+use std::f32::consts::{FRAC_PI_2, TAU};
+use std::time::Duration;
 
 pub struct CaptureUiPlugin;
 impl Plugin for CaptureUiPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, tick_timer)
-            .add_observer(capture_incomplete::<CaptureLineCollision>)
-            .add_observer(capture_status_changed);
+        app.add_systems(
+            Update,
+            (
+                tick_timer,
+                update_gauges,
+                shake_gauges,
+                update_line_budget,
+                float_popups,
+            ),
+        )
+        .add_systems(Startup, spawn_line_budget_meter)
+        .add_systems(
+            Update,
+            dismiss_results.run_if(binding_just_pressed(|bindings| bindings.confirm)),
+        )
+        .add_observer(capture_incomplete)
+        .add_observer(capture_status_changed)
+        .add_observer(show_progress_popup)
+        .add_observer(show_results);
     }
 }
 
+/// How long before progress starts to decay that the gauge warns the player.
+const DECAY_WARNING: Duration = Duration::from_secs(1);
+
+const METER_BACKGROUND_COLOR: Color = Color::linear_rgba(0.066, 0.060, 0.060, 0.624);
+
+const RESULTS_BACKGROUND_COLOR: Color = Color::linear_rgba(0.066, 0.060, 0.060, 0.824);
+
+/// How long the results screen stays up if it isn't dismissed.
+const RESULTS_SECONDS: f32 = 8.;
+
+/// How long a gauge stays up after its creature's progress last changed.
+const GAUGE_SECONDS: f32 = 10.;
+
+/// The outer radius of a gauge, which is just outside of a creature's collider.
+const GAUGE_RADIUS: f32 = 22.;
+
+const GAUGE_WIDTH: f32 = 3.;
+
+/// How many segments a full gauge is made of.
+const GAUGE_SEGMENTS: usize = 32;
+
+const SHAKE_SECONDS: f32 = 0.3;
+
+/// How many times per second a shaking gauge goes from side to side.
+const SHAKE_FREQUENCY: f32 = 12.;
+
+/// How far a shaking gauge moves to either side.
+const SHAKE_DISTANCE: f32 = 2.;
+
+/// How long a progress popup stays up.
+const POPUP_SECONDS: f32 = 0.8;
+
+/// How fast progress popups float upwards, in world units per second.
+const POPUP_SPEED: f32 = 24.;
+
 #[derive(Component, Reflect, Debug, Deref, DerefMut)]
 #[reflect(Component)]
 struct TextDisappearTimer(Timer);
+
+/// Shows how much more line can be drawn before the oldest points are dropped.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct LineBudgetMeter;
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct LineBudgetFill;
+
+/// A ring around a creature that fills up as it is looped.
+#[derive(Component, Reflect, Debug, Default, PartialEq)]
+#[reflect(Component)]
+pub(crate) struct CaptureGauge {
+    /// How much of the ring is currently filled, from `0.` to `1.`.
+    pub(crate) fraction: f32,
+}
+
 #[derive(Component, Reflect, Debug)]
 #[reflect(Component)]
-struct CaptureCountText;
+struct CaptureGaugeFill;
+
+/// Shakes a gauge from side to side after its creature's progress decays.
+#[derive(Component, Reflect, Debug, Deref, DerefMut)]
+#[reflect(Component)]
+struct GaugeShake(Timer);
+
+/// Floats up from a creature to show how much progress a loop gave it.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct ProgressPopup;
+
+/// Shows how well the player did after capturing a creature.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct ResultsScreen;
 
 fn tick_timer(
     timer: Query<(Entity, &mut TextDisappearTimer), Without<Despawn>>,
@@ -33,64 +123,312 @@ fn tick_timer(
     }
 }
 
-fn capture_incomplete<T>(
-    _: Trigger<T>,
-    capture_ui: Query<Entity, With<CaptureCountText>>,
+fn spawn_line_budget_meter(mut commands: Commands) {
+    commands.spawn((
+        Name::from("Line Budget Meter"),
+        LineBudgetMeter,
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(8.),
+            left: Val::Percent(35.),
+            width: Val::Percent(30.),
+            height: Val::Px(6.),
+            ..default()
+        },
+        BackgroundColor(METER_BACKGROUND_COLOR),
+        Visibility::Hidden,
+        children![(
+            LineBudgetFill,
+            Node {
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                ..default()
+            },
+            BackgroundColor(Color::WHITE),
+        )],
+    ));
+}
+
+fn update_line_budget(
+    line: Option<Single<&CaptureLine>>,
+    meter: Single<&mut Visibility, With<LineBudgetMeter>>,
+    fill: Single<(&mut Node, &mut BackgroundColor), With<LineBudgetFill>>,
+) {
+    let mut visibility = meter.into_inner();
+    let budget = line.and_then(|line| {
+        let max = line.max_line_length? as f32;
+        Some((line.remaining_length()? / max, line.end_color))
+    });
+
+    let Some((fraction, color)) = budget else {
+        *visibility = Visibility::Hidden;
+        return;
+    };
+
+    let (mut node, mut background) = fill.into_inner();
+    *visibility = Visibility::Inherited;
+    node.width = Val::Percent(fraction * 100.);
+    background.0 = color.unwrap_or(Color::WHITE);
+}
+
+/// Fills each gauge with its creature's progress, coloured by whether it is complete or about to
+/// decay.
+fn update_gauges(
+    time: Res<Time>,
+    settings: Res<Settings>,
+    creatures: Query<(
+        &CaptureProgress,
+        &CaptureRequirements,
+        Option<&CaptureDecay>,
+    )>,
+    gauges: Query<(&mut CaptureGauge, &ChildOf, &Children)>,
+    fills: Query<(&Mesh2d, &MeshMaterial2d<ColorMaterial>), With<CaptureGaugeFill>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let palette = settings.palette.colors();
+    for (mut gauge, child_of, children) in gauges {
+        let Ok((progress, requirements, decay)) = creatures.get(child_of.parent()) else {
+            continue;
+        };
+        let Some((mesh, material)) = children.iter().find_map(|child| fills.get(child).ok()) else {
+            continue;
+        };
+
+        let fraction = (progress.progress as f32 / requirements.0.max(1) as f32).min(1.);
+        if gauge.set_if_neq(CaptureGauge { fraction }) {
+            if let Some(mesh) = meshes.get_mut(&mesh.0) {
+                *mesh = ring_mesh(fraction);
+            }
+        }
+
+        let color = if progress.progress >= requirements.0 {
+            palette.success
+        } else {
+            match decay.and_then(|decay| decay.starts_in(progress, time.elapsed())) {
+                Some(remaining) if remaining <= DECAY_WARNING => palette.warning,
+                _ => palette.text,
+            }
+        };
+        if let Some(material) = materials.get_mut(&material.0) {
+            material.color = color;
+        }
+    }
+}
+
+fn shake_gauges(
     mut commands: Commands,
+    gauges: Query<(Entity, &mut Transform, &mut GaugeShake)>,
+    settings: Res<Settings>,
+    time: Res<Time>,
 ) {
-    for element in capture_ui.iter() {
-        commands.entity(element).insert(Despawn);
+    for (entity, mut transform, mut shake) in gauges {
+        shake.tick(time.delta());
+        transform.translation.x = if shake.finished() || settings.reduced_motion {
+            0.
+        } else {
+            (shake.elapsed_secs() * SHAKE_FREQUENCY * TAU).sin() * SHAKE_DISTANCE
+        };
+
+        if shake.finished() {
+            commands.entity(entity).remove::<GaugeShake>();
+        }
+    }
+}
+
+/// The part of the gauge ring that is filled, going clockwise from the top.
+fn ring_mesh(fraction: f32) -> Mesh {
+    let steps = ((GAUGE_SEGMENTS as f32 * fraction).ceil() as usize).max(1);
+    let positions = (0..=steps)
+        .flat_map(|step| {
+            let angle = TAU * fraction * step as f32 / steps as f32;
+            let direction = Vec2::from_angle(FRAC_PI_2 - angle);
+            [
+                direction * GAUGE_RADIUS,
+                direction * (GAUGE_RADIUS - GAUGE_WIDTH),
+            ]
+        })
+        .map(|position| position.extend(0.).to_array())
+        .collect::<Vec<_>>();
+
+    Mesh::new(
+        PrimitiveTopology::TriangleStrip,
+        RenderAssetUsages::default(),
+    )
+    .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+}
+
+fn capture_incomplete(
+    changed: Trigger<CaptureSessionChanged>,
+    gauges: Query<Entity, With<CaptureGauge>>,
+    mut commands: Commands,
+) {
+    // Progress is only lost when the line breaks or the capture is cancelled.
+    if !matches!(
+        changed.ended(),
+        Some(EndReason::Hit | EndReason::LeftWindow)
+    ) {
+        return;
+    }
+
+    for gauge in gauges.iter() {
+        commands.entity(gauge).insert(Despawn);
     }
 }
 
 fn capture_status_changed(
     changed: Trigger<CaptureProgressChanged>,
-    creatures: Query<
-        (
-            Entity,
-            &CaptureProgress,
-            &CaptureRequirements,
-            Option<&Children>,
-        ),
-        With<Creature>,
-    >,
-    mut existing_ui: Query<
-        (&mut Text2d, &mut TextColor, &mut TextDisappearTimer),
-        With<CaptureCountText>,
-    >,
+    creatures: Query<Option<&Children>, With<Creature>>,
+    mut gauges: Query<&mut TextDisappearTimer, With<CaptureGauge>>,
+    settings: Res<Settings>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
     mut commands: Commands,
 ) {
-    let (creature, progress, requirements, children) = match creatures.get(changed.0) {
-        Ok(creature) => creature,
-        Err(_) => return,
+    let Ok(children) = creatures.get(changed.creature) else {
+        return;
     };
 
-    let remaining = requirements.0.saturating_sub(progress.0);
-
-    if let Some(children) = children {
-        let (mut text, mut color, mut timer) = existing_ui.get_mut(children[0]).unwrap();
-        timer.reset();
-        if remaining == 0 {
-            *text = Text2d::new("OK");
-            *color = TextColor(color::palettes::css::LIGHT_SEA_GREEN.into());
-        } else {
-            *text = Text2d::from(remaining.to_string());
+    let existing = children
+        .into_iter()
+        .flatten()
+        .copied()
+        .find(|child| gauges.contains(*child));
+    let gauge = match existing {
+        Some(gauge) => {
+            if let Ok(mut timer) = gauges.get_mut(gauge) {
+                timer.reset();
+            }
+            gauge
         }
-    } else if remaining == 0 {
-        commands.entity(creature).with_child((
-            TextDisappearTimer(Timer::from_seconds(10., TimerMode::Once)),
-            Text2d::from("OK"),
-            TextColor(color::palettes::css::LIGHT_SEA_GREEN.into()),
-            CaptureCountText,
-            TextLayout::new_with_justify(JustifyText::Center),
-        ));
-    } else {
-        commands.entity(creature).with_child((
-            TextDisappearTimer(Timer::from_seconds(10., TimerMode::Once)),
-            Text2d::from(remaining.to_string()),
-            TextColor(Color::WHITE),
-            CaptureCountText,
-            TextLayout::new_with_justify(JustifyText::Center),
-        ));
+        None => {
+            let palette = settings.palette.colors();
+            commands
+                .spawn((
+                    Name::from("Capture Gauge"),
+                    CaptureGauge::default(),
+                    TextDisappearTimer(Timer::from_seconds(GAUGE_SECONDS, TimerMode::Once)),
+                    Transform::from_xyz(0., 0., 1.),
+                    Visibility::default(),
+                    ChildOf(changed.creature),
+                    children![
+                        (
+                            Mesh2d(
+                                meshes.add(Annulus::new(GAUGE_RADIUS - GAUGE_WIDTH, GAUGE_RADIUS))
+                            ),
+                            MeshMaterial2d(materials.add(palette.panel)),
+                        ),
+                        (
+                            CaptureGaugeFill,
+                            Mesh2d(meshes.add(ring_mesh(0.))),
+                            MeshMaterial2d(materials.add(palette.text)),
+                            Transform::from_xyz(0., 0., 0.1),
+                        ),
+                    ],
+                ))
+                .id()
+        }
+    };
+
+    if changed.change < 0 {
+        commands
+            .entity(gauge)
+            .insert(GaugeShake(Timer::from_seconds(
+                SHAKE_SECONDS,
+                TimerMode::Once,
+            )));
+    }
+}
+
+fn show_progress_popup(
+    changed: Trigger<CaptureProgressChanged>,
+    creatures: Query<&GlobalTransform, With<Creature>>,
+    settings: Res<Settings>,
+    mut commands: Commands,
+) {
+    let Ok(creature) = creatures.get(changed.creature) else {
+        return;
+    };
+    if changed.change <= 0 {
+        return;
+    }
+
+    commands.spawn((
+        ProgressPopup,
+        TextDisappearTimer(Timer::from_seconds(POPUP_SECONDS, TimerMode::Once)),
+        Text2d::new(format!("+{}", changed.change)),
+        TextColor(settings.palette.colors().success),
+        Transform::from_translation(creature.translation() + Vec3::new(0., 16., 1.)),
+    ));
+}
+
+fn float_popups(
+    popups: Query<&mut Transform, With<ProgressPopup>>,
+    settings: Res<Settings>,
+    time: Res<Time>,
+) {
+    if settings.reduced_motion {
+        return;
+    }
+
+    for mut transform in popups {
+        transform.translation.y += POPUP_SPEED * time.delta_secs();
+    }
+}
+
+fn show_results(
+    scored: Trigger<CaptureScored>,
+    existing: Query<Entity, With<ResultsScreen>>,
+    mut commands: Commands,
+) {
+    for screen in existing {
+        commands.entity(screen).insert(Despawn);
+    }
+
+    let score = &scored.score;
+    let species = scored
+        .species
+        .as_ref()
+        .map_or("Creature", |species| species.0.as_str());
+    let mut lines = vec![
+        format!("{species} captured!"),
+        format!("Time: {:.1}s", score.time.as_secs_f32()),
+        format!("Loops: {}", score.loops),
+        format!("Overshot by: {}", score.overshot_by),
+        format!("Damage taken: {}", score.damage_taken),
+        format!("Line drawn: {:.0}", score.line_length),
+        format!("Rank: {} ({})", score.rank(), score.points()),
+    ];
+    if scored.new_best {
+        lines.push("New best!".into());
+    }
+
+    commands
+        .spawn((
+            Name::from("Results Screen"),
+            ResultsScreen,
+            TextDisappearTimer(Timer::from_seconds(RESULTS_SECONDS, TimerMode::Once)),
+            Node {
+                position_type: PositionType::Absolute,
+                align_self: AlignSelf::Center,
+                justify_self: JustifySelf::Center,
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                padding: UiRect::all(Val::Px(12.)),
+                row_gap: Val::Px(4.),
+                ..default()
+            },
+            BackgroundColor(RESULTS_BACKGROUND_COLOR),
+        ))
+        .with_children(|screen| {
+            for line in lines {
+                screen.spawn(Text::new(line));
+            }
+        });
+}
+
+fn dismiss_results(screens: Query<Entity, With<ResultsScreen>>, mut commands: Commands) {
+    for screen in screens {
+        commands.entity(screen).insert(Despawn);
     }
 }
@@ -1,9 +1,19 @@
 mod behavior;
-mod types;
+pub(crate) mod guide;
+pub(crate) mod roster;
+pub(crate) mod species;
+pub mod types;
 
+use crate::capture::assist::Assist;
 use crate::capture::Damage;
+use crate::creature::guide::FieldGuidePlugin;
+use crate::creature::roster::RosterPlugin;
+use crate::creature::species::SpeciesPlugin;
+use crate::creature::types::{CreatureType, TypesPlugin};
 use avian2d::prelude::{Collider, RigidBody};
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
 pub struct CreaturePlugin;
 impl Plugin for CreaturePlugin {
@@ -11,6 +21,13 @@ impl Plugin for CreaturePlugin {
         app.register_type::<Creature>()
             .register_type::<CaptureProgress>()
             .register_type::<CaptureRequirements>()
+            .register_type::<CaptureDecay>()
+            .register_type::<Species>()
+            .register_type::<FieldMoves>()
+            .add_plugins(FieldGuidePlugin)
+            .add_plugins(RosterPlugin)
+            .add_plugins(SpeciesPlugin)
+            .add_plugins(TypesPlugin)
             .add_systems(Startup, spawn_enemy);
     }
 }
@@ -19,13 +36,92 @@ impl Plugin for CreaturePlugin {
 #[reflect(Component)]
 pub struct Creature;
 
+/// The species a creature belongs to.
+#[derive(Component, Reflect, Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[reflect(Component)]
+pub struct Species(pub String);
+
+/// A move a creature can use in the overworld to clear an obstacle.
+#[derive(Reflect, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FieldMove {
+    Cut,
+    Strength,
+    Surf,
+}
+
+impl FieldMove {
+    /// Reads a field move from the name of its LDtk enum value.
+    pub fn from_ldtk(value: &str) -> Option<Self> {
+        match value {
+            "Cut" => Some(FieldMove::Cut),
+            "Strength" => Some(FieldMove::Strength),
+            "Surf" => Some(FieldMove::Surf),
+            _ => None,
+        }
+    }
+}
+
+/// The field moves a creature knows.
+#[derive(Component, Reflect, Debug, Clone, Default, Deref, DerefMut)]
+#[reflect(Component)]
+pub struct FieldMoves(pub Vec<FieldMove>);
+
 #[derive(Component, Reflect, Debug, Deref, DerefMut)]
 #[reflect(Component)]
 pub struct CaptureRequirements(pub u32);
 
 #[derive(Component, Reflect, Debug, Default, Deref, DerefMut)]
 #[reflect(Component)]
-pub struct CaptureProgress(pub u32);
+pub struct CaptureProgress {
+    #[deref]
+    pub progress: u32,
+
+    /// When the creature was last looped, as time elapsed since startup.
+    pub last_loop: Option<Duration>,
+}
+
+impl CaptureProgress {
+    /// Adds progress from a loop made at `now`.
+    pub fn add(&mut self, amount: u32, now: Duration) {
+        self.progress += amount;
+        self.last_loop = Some(now);
+    }
+
+    pub fn reset(&mut self) {
+        self.progress = 0;
+        self.last_loop = None;
+    }
+}
+
+/// How quickly a creature's [`CaptureProgress`] drains when it isn't being looped.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct CaptureDecay {
+    /// How long after the last loop before progress starts to drain.
+    pub delay: Duration,
+
+    /// Removes one progress each time it finishes.
+    pub timer: Timer,
+}
+
+impl CaptureDecay {
+    pub fn new(delay: Duration, interval: Duration) -> Self {
+        Self {
+            delay,
+            timer: Timer::new(interval, TimerMode::Repeating),
+        }
+    }
+
+    /// How long until the given progress starts to drain, if it will at all.
+    pub fn starts_in(&self, progress: &CaptureProgress, now: Duration) -> Option<Duration> {
+        if progress.progress == 0 {
+            return None;
+        }
+
+        let last_loop = progress.last_loop?;
+        Some((last_loop + self.delay).saturating_sub(now))
+    }
+}
 
 #[derive(Component, Reflect, Debug)]
 #[reflect(Component)]
@@ -35,16 +131,31 @@ pub struct TestAttack(u32);
 #[reflect(Component)]
 pub struct Attack;
 
-fn spawn_enemy(mut commands: Commands, asset_server: Res<AssetServer>) {
-    let enemy_icon = asset_server.load("TempEnemy.png");
-    commands.spawn((
-        Name::from("Testmon"),
+/// The components every wild creature of a species starts with.
+pub(crate) fn creature_bundle(species: &str, icon: Handle<Image>) -> impl Bundle {
+    (
+        Name::from(species),
         CaptureProgress::default(),
         Creature,
-        TestAttack(1),
+        Species(species.into()),
         CaptureRequirements(3),
+        CreatureType::default(),
+        CaptureDecay::new(Duration::from_secs(2), Duration::from_secs(1)),
         Collider::rectangle(32., 32.),
-        Sprite::from_image(enemy_icon),
+        Sprite::from_image(icon),
+    )
+}
+
+fn spawn_enemy(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let enemy_icon = asset_server.load("TempEnemy.png");
+    commands.spawn((
+        creature_bundle("Testmon", enemy_icon),
+        TestAttack(1),
+        Assist::ProgressBurst {
+            radius: 64.,
+            amount: 2,
+        },
+        FieldMoves(vec![FieldMove::Cut]),
     ));
 }
 
@@ -0,0 +1,234 @@
+mod menu;
+
+use crate::capture::score::{CaptureScored, Rank};
+use crate::capture::session::{CaptureSessionChanged, SessionState};
+use crate::capture::Captured;
+use crate::creature::guide::menu::FieldGuideMenuPlugin;
+use crate::creature::{Creature, Species};
+use crate::save::{SaveFile, SavePlugin};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Tracks which species the player has seen and captured, and lets them browse it.
+pub struct FieldGuidePlugin;
+impl Plugin for FieldGuidePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<FieldGuide>()
+            .add_plugins(SavePlugin::<FieldGuide>::default())
+            .add_plugins(FieldGuideMenuPlugin)
+            .add_observer(see_species)
+            .add_observer(capture_species)
+            .add_observer(rank_species);
+    }
+}
+
+/// How much the player knows about a species, from least to most.
+#[derive(
+    Reflect, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default,
+)]
+pub enum GuideStatus {
+    #[default]
+    Unseen,
+
+    /// A capture was started while one was around.
+    Seen,
+    Captured,
+}
+
+/// What the field guide records about a species.
+#[derive(Reflect, Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct GuideEntry {
+    pub status: GuideStatus,
+
+    /// The best rank of any capture of the species.
+    pub best_rank: Option<Rank>,
+}
+
+/// Every species the player has come across, keyed by name, saved whenever it changes.
+#[derive(Resource, Reflect, Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[reflect(Resource)]
+#[serde(default)]
+pub struct FieldGuide {
+    entries: HashMap<String, GuideEntry>,
+}
+
+impl SaveFile for FieldGuide {
+    const FILE_NAME: &'static str = "field_guide.ron";
+    const DESCRIPTION: &'static str = "the field guide";
+}
+
+impl FieldGuide {
+    /// What is known about a species, which is nothing if it has never been seen.
+    pub fn entry(&self, species: &str) -> GuideEntry {
+        self.entries.get(species).cloned().unwrap_or_default()
+    }
+}
+
+/// Raises a species to `status`, leaving it alone if the guide already knows as much so that
+/// the guide is only saved when something changes.
+fn discover(guide: &mut ResMut<FieldGuide>, species: &Species, status: GuideStatus) {
+    if guide.entry(&species.0).status < status {
+        guide.entries.entry(species.0.clone()).or_default().status = status;
+    }
+}
+
+fn see_species(
+    changed: Trigger<CaptureSessionChanged>,
+    creatures: Query<&Species, (With<Creature>, Without<Captured>)>,
+    mut guide: ResMut<FieldGuide>,
+) {
+    if changed.state != SessionState::Started {
+        return;
+    }
+
+    for species in creatures {
+        discover(&mut guide, species, GuideStatus::Seen);
+    }
+}
+
+fn capture_species(
+    changed: Trigger<CaptureSessionChanged>,
+    species: Query<&Species>,
+    mut guide: ResMut<FieldGuide>,
+) {
+    for captured in changed.captured() {
+        if let Ok(species) = species.get(captured.creature) {
+            discover(&mut guide, species, GuideStatus::Captured);
+        }
+    }
+}
+
+fn rank_species(scored: Trigger<CaptureScored>, mut guide: ResMut<FieldGuide>) {
+    let Some(species) = &scored.species else {
+        return;
+    };
+
+    let rank = scored.score.rank();
+    if guide
+        .entry(&species.0)
+        .best_rank
+        .is_none_or(|best| rank < best)
+    {
+        guide
+            .entries
+            .entry(species.0.clone())
+            .or_default()
+            .best_rank = Some(rank);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::capture::score::CaptureScore;
+    use crate::capture::session::{CapturedCreature, EndReason};
+    use std::time::Duration;
+
+    fn guide_world() -> World {
+        let mut world = World::new();
+        world.init_resource::<FieldGuide>();
+        world.add_observer(see_species);
+        world.add_observer(capture_species);
+        world.add_observer(rank_species);
+        world.flush();
+        world
+    }
+
+    fn change_session(world: &mut World, state: SessionState) {
+        let session = world.spawn_empty().id();
+        world.trigger(CaptureSessionChanged { session, state });
+        world.flush();
+    }
+
+    fn score(world: &mut World, damage_taken: u32) {
+        let captured = world.spawn_empty().id();
+        world.trigger(CaptureScored {
+            captured,
+            species: Some(Species("Mossling".into())),
+            score: CaptureScore {
+                time: Duration::ZERO,
+                loops: 0,
+                overshot_by: 0,
+                damage_taken,
+                line_length: 0.,
+            },
+            new_best: false,
+        });
+        world.flush();
+    }
+
+    #[test]
+    fn seeing_a_captured_species_does_not_downgrade_it() {
+        let mut world = guide_world();
+        let creature = world.spawn((Creature, Species("Mossling".into()))).id();
+
+        change_session(&mut world, SessionState::Started);
+        assert_eq!(
+            world.resource::<FieldGuide>().entry("Mossling").status,
+            GuideStatus::Seen
+        );
+
+        change_session(
+            &mut world,
+            SessionState::Ended {
+                reason: EndReason::Success {
+                    captured: vec![CapturedCreature {
+                        creature,
+                        overshot_by: 0,
+                    }],
+                    failed: vec![],
+                },
+            },
+        );
+        change_session(&mut world, SessionState::Started);
+        assert_eq!(
+            world.resource::<FieldGuide>().entry("Mossling").status,
+            GuideStatus::Captured
+        );
+    }
+
+    #[test]
+    fn rank_species_keeps_the_best_rank() {
+        let mut world = guide_world();
+        let best_rank = |world: &World| world.resource::<FieldGuide>().entry("Mossling").best_rank;
+
+        score(&mut world, 3);
+        assert_eq!(best_rank(&world), Some(Rank::A));
+
+        score(&mut world, 9);
+        assert_eq!(best_rank(&world), Some(Rank::A));
+
+        score(&mut world, 0);
+        assert_eq!(best_rank(&world), Some(Rank::S));
+    }
+
+    #[test]
+    fn field_guide_round_trips_through_its_save_file() {
+        let mut guide = FieldGuide::default();
+        guide.entries.insert(
+            "Mossling".into(),
+            GuideEntry {
+                status: GuideStatus::Captured,
+                best_rank: Some(Rank::B),
+            },
+        );
+        guide.entries.insert(
+            "Emberkit".into(),
+            GuideEntry {
+                status: GuideStatus::Seen,
+                best_rank: None,
+            },
+        );
+
+        let path = std::env::temp_dir()
+            .join(format!("bevymon_ranger_guide_{}", std::process::id()))
+            .join(FieldGuide::FILE_NAME);
+        guide.write_to(&path).unwrap();
+        let read = FieldGuide::read_from(&path);
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+
+        assert_eq!(read.unwrap(), guide);
+    }
+}
@@ -0,0 +1,304 @@
+use crate::creature::guide::{FieldGuide, GuideEntry, GuideStatus};
+use crate::creature::species::{KnownSpecies, SpeciesData};
+use crate::settings::{binding_just_pressed, rebinding_key};
+use crate::ui::{BUTTON_COLOR, FONT_SIZE, MENU_BACKGROUND_COLOR};
+use bevy::prelude::*;
+use std::cmp::Ordering;
+
+const SPRITE_SIZE: f32 = 16.;
+
+/// What the sprites of unseen species are tinted, so that only their outline shows.
+const SILHOUETTE_COLOR: Color = Color::BLACK;
+
+const COLUMNS: [&str; 5] = ["", "Species", "Type", "Habitat", "Best"];
+
+pub(super) struct FieldGuideMenuPlugin;
+impl Plugin for FieldGuideMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<FieldGuideMenu>()
+            .register_type::<GuideView>()
+            .register_type::<GuideButton>()
+            .init_resource::<GuideView>()
+            .add_systems(Startup, spawn_field_guide_menu)
+            .add_systems(
+                Update,
+                (
+                    toggle_field_guide_menu.run_if(
+                        binding_just_pressed(|bindings| bindings.field_guide)
+                            .and(not(rebinding_key)),
+                    ),
+                    press_guide_buttons,
+                    label_guide_buttons.run_if(resource_changed::<GuideView>),
+                    list_species.run_if(
+                        resource_changed::<FieldGuide>
+                            .or(resource_changed::<GuideView>)
+                            .or(resource_changed::<KnownSpecies>),
+                    ),
+                )
+                    .chain(),
+            );
+    }
+}
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct FieldGuideMenu;
+
+/// The grid that each species is listed in.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct GuideList;
+
+/// How the species in the field guide are ordered.
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum GuideSort {
+    #[default]
+    Name,
+
+    /// Captured species first, then seen, then unseen.
+    Status,
+
+    /// Best capture rank first, with species that have never been ranked last.
+    Rank,
+}
+
+impl GuideSort {
+    fn next(self) -> Self {
+        match self {
+            GuideSort::Name => GuideSort::Status,
+            GuideSort::Status => GuideSort::Rank,
+            GuideSort::Rank => GuideSort::Name,
+        }
+    }
+
+    fn compare(
+        self,
+        (a, a_entry): (&str, &GuideEntry),
+        (b, b_entry): (&str, &GuideEntry),
+    ) -> Ordering {
+        let order = match self {
+            GuideSort::Name => Ordering::Equal,
+            GuideSort::Status => b_entry.status.cmp(&a_entry.status),
+            GuideSort::Rank => (a_entry.best_rank.is_none(), a_entry.best_rank)
+                .cmp(&(b_entry.best_rank.is_none(), b_entry.best_rank)),
+        };
+        order.then_with(|| a.cmp(b))
+    }
+}
+
+/// Which species the field guide shows.
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum GuideFilter {
+    #[default]
+    All,
+    Unseen,
+    Seen,
+    Captured,
+}
+
+impl GuideFilter {
+    fn next(self) -> Self {
+        match self {
+            GuideFilter::All => GuideFilter::Unseen,
+            GuideFilter::Unseen => GuideFilter::Seen,
+            GuideFilter::Seen => GuideFilter::Captured,
+            GuideFilter::Captured => GuideFilter::All,
+        }
+    }
+
+    fn shows(self, status: GuideStatus) -> bool {
+        match self {
+            GuideFilter::All => true,
+            GuideFilter::Unseen => status == GuideStatus::Unseen,
+            GuideFilter::Seen => status == GuideStatus::Seen,
+            GuideFilter::Captured => status == GuideStatus::Captured,
+        }
+    }
+}
+
+#[derive(Resource, Reflect, Debug, Default)]
+#[reflect(Resource)]
+struct GuideView {
+    sort: GuideSort,
+    filter: GuideFilter,
+}
+
+/// A button that changes how the field guide is shown, labelled with its current choice.
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+#[reflect(Component)]
+enum GuideButton {
+    Sort,
+    Filter,
+}
+
+impl GuideButton {
+    fn label(self, view: &GuideView) -> String {
+        match self {
+            GuideButton::Sort => format!("Sort: {:?}", view.sort),
+            GuideButton::Filter => format!("Show: {:?}", view.filter),
+        }
+    }
+}
+
+fn guide_button(button: GuideButton) -> impl Bundle {
+    (
+        Button,
+        button,
+        Node {
+            padding: UiRect::horizontal(Val::Px(4.)),
+            ..default()
+        },
+        BackgroundColor(BUTTON_COLOR),
+        children![(Text::default(), TextFont::from_font_size(FONT_SIZE))],
+    )
+}
+
+fn spawn_field_guide_menu(mut commands: Commands) {
+    commands.spawn((
+        Name::from("Field Guide"),
+        FieldGuideMenu,
+        Node {
+            position_type: PositionType::Absolute,
+            align_self: AlignSelf::Center,
+            justify_self: JustifySelf::Center,
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(6.),
+            padding: UiRect::all(Val::Px(12.)),
+            ..default()
+        },
+        BackgroundColor(MENU_BACKGROUND_COLOR),
+        GlobalZIndex(1),
+        Visibility::Hidden,
+        children![
+            (
+                Node {
+                    column_gap: Val::Px(6.),
+                    ..default()
+                },
+                children![
+                    guide_button(GuideButton::Sort),
+                    guide_button(GuideButton::Filter)
+                ],
+            ),
+            (
+                GuideList,
+                Node {
+                    display: Display::Grid,
+                    grid_template_columns: vec![GridTrack::auto(); COLUMNS.len()],
+                    column_gap: Val::Px(6.),
+                    row_gap: Val::Px(4.),
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+            ),
+        ],
+    ));
+}
+
+fn toggle_field_guide_menu(menu: Single<&mut Visibility, With<FieldGuideMenu>>) {
+    let mut visibility = menu.into_inner();
+    *visibility = match *visibility {
+        Visibility::Hidden => Visibility::Inherited,
+        _ => Visibility::Hidden,
+    };
+}
+
+fn press_guide_buttons(
+    mut view: ResMut<GuideView>,
+    buttons: Query<(&Interaction, &GuideButton), Changed<Interaction>>,
+) {
+    for (interaction, button) in buttons {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        match button {
+            GuideButton::Sort => view.sort = view.sort.next(),
+            GuideButton::Filter => view.filter = view.filter.next(),
+        }
+    }
+}
+
+/// The text cells of a species' row, hiding what it is until it has been seen.
+fn species_row(name: &str, data: &SpeciesData, entry: &GuideEntry) -> [String; 4] {
+    if entry.status == GuideStatus::Unseen {
+        return ["???".into(), "?".into(), "?".into(), "-".into()];
+    }
+
+    let habitats = if data.habitats.is_empty() {
+        "Unknown".into()
+    } else {
+        data.habitats.join(", ")
+    };
+    let rank = entry
+        .best_rank
+        .map_or_else(|| "-".into(), |rank| rank.to_string());
+    let status = match entry.status {
+        GuideStatus::Captured => "*",
+        _ => "",
+    };
+    [
+        format!("{name}{status}"),
+        format!("{:?}", data.creature_type),
+        habitats,
+        rank,
+    ]
+}
+
+fn label_guide_buttons(
+    view: Res<GuideView>,
+    buttons: Query<(&GuideButton, &Children)>,
+    mut labels: Query<&mut Text>,
+) {
+    for (button, children) in buttons {
+        for child in children {
+            if let Ok(mut text) = labels.get_mut(*child) {
+                text.0 = button.label(&view);
+            }
+        }
+    }
+}
+
+fn list_species(
+    mut commands: Commands,
+    guide: Res<FieldGuide>,
+    view: Res<GuideView>,
+    known: Res<KnownSpecies>,
+    asset_server: Res<AssetServer>,
+    list: Single<Entity, With<GuideList>>,
+) {
+    let mut species = known
+        .iter()
+        .map(|(name, data)| (name.as_str(), data, guide.entry(name)))
+        .filter(|(_, _, entry)| view.filter.shows(entry.status))
+        .collect::<Vec<_>>();
+    species
+        .sort_by(|(a, _, a_entry), (b, _, b_entry)| view.sort.compare((a, a_entry), (b, b_entry)));
+
+    let list = list.into_inner();
+    commands.entity(list).despawn_related::<Children>();
+    commands.entity(list).with_children(|list| {
+        for column in COLUMNS {
+            list.spawn((Text::new(column), TextFont::from_font_size(FONT_SIZE)));
+        }
+
+        for (name, data, entry) in species {
+            let mut sprite = ImageNode::new(asset_server.load(&data.sprite));
+            if entry.status == GuideStatus::Unseen {
+                sprite.color = SILHOUETTE_COLOR;
+            }
+            list.spawn((
+                sprite,
+                Node {
+                    width: Val::Px(SPRITE_SIZE),
+                    height: Val::Px(SPRITE_SIZE),
+                    ..default()
+                },
+            ));
+
+            for cell in species_row(name, data, &entry) {
+                list.spawn((Text::new(cell), TextFont::from_font_size(FONT_SIZE)));
+            }
+        }
+    });
+}
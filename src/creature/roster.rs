@@ -0,0 +1,131 @@
+use crate::capture::assist::Assist;
+use crate::capture::session::CaptureSessionChanged;
+use crate::creature::{FieldMove, FieldMoves, Species};
+use crate::settings::{binding_just_pressed, rebinding_key};
+use crate::Despawn;
+use bevy::prelude::*;
+
+pub struct RosterPlugin;
+impl Plugin for RosterPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ChoosePartner>()
+            .register_type::<Roster>()
+            .register_type::<RosterEntry>()
+            .init_resource::<Roster>()
+            .add_systems(
+                Update,
+                (
+                    switch_partner.run_if(
+                        binding_just_pressed(|bindings| bindings.partner).and(not(rebinding_key)),
+                    ),
+                    choose_partner.run_if(on_event::<ChoosePartner>),
+                )
+                    .chain(),
+            )
+            .add_observer(record_captured_creatures);
+    }
+}
+
+/// A creature that the player has captured.
+#[derive(Reflect, Debug, Clone)]
+pub struct RosterEntry {
+    pub species: Species,
+    pub nickname: String,
+
+    /// The assist this creature provides while it is the partner.
+    pub assist: Option<Assist>,
+
+    pub field_moves: Vec<FieldMove>,
+}
+
+/// Every creature the player has captured, and which one (if any) is their partner.
+#[derive(Resource, Reflect, Debug, Default)]
+#[reflect(Resource)]
+pub struct Roster {
+    pub creatures: Vec<RosterEntry>,
+
+    /// The index into `creatures` of the active partner.
+    pub partner: Option<usize>,
+}
+
+impl Roster {
+    pub fn partner(&self) -> Option<&RosterEntry> {
+        self.partner.and_then(|i| self.creatures.get(i))
+    }
+
+    /// Whether any captured creature knows the given field move.
+    #[cfg(feature = "overworld")]
+    pub fn has_field_move(&self, field_move: FieldMove) -> bool {
+        self.creatures
+            .iter()
+            .any(|creature| creature.field_moves.contains(&field_move))
+    }
+}
+
+/// Requests that the creature at the given roster index becomes the active partner.
+///
+/// `None` will dismiss the current partner.
+#[derive(Event, Debug)]
+pub struct ChoosePartner(pub Option<usize>);
+
+#[allow(clippy::type_complexity)]
+fn record_captured_creatures(
+    changed: Trigger<CaptureSessionChanged>,
+    creatures: Query<(
+        &Species,
+        Option<&Name>,
+        Option<&Assist>,
+        Option<&FieldMoves>,
+    )>,
+    mut roster: ResMut<Roster>,
+    mut commands: Commands,
+) {
+    for captured in changed.captured() {
+        let Ok((species, name, assist, field_moves)) = creatures.get(captured.creature) else {
+            continue;
+        };
+
+        let nickname = match name {
+            Some(name) => name.to_string(),
+            None => species.0.clone(),
+        };
+
+        roster.creatures.push(RosterEntry {
+            species: species.clone(),
+            nickname,
+            assist: assist.cloned(),
+            field_moves: field_moves.map(|moves| moves.0.clone()).unwrap_or_default(),
+        });
+
+        if roster.partner.is_none() {
+            roster.partner = Some(roster.creatures.len() - 1);
+        }
+
+        commands.entity(captured.creature).insert(Despawn);
+    }
+}
+
+/// Makes the next creature in the roster the partner, going without one after the last.
+fn switch_partner(roster: Res<Roster>, mut choose: EventWriter<ChoosePartner>) {
+    if roster.creatures.is_empty() {
+        return;
+    }
+
+    let next = match roster.partner {
+        Some(i) if i + 1 < roster.creatures.len() => Some(i + 1),
+        Some(_) => None,
+        None => Some(0),
+    };
+    choose.write(ChoosePartner(next));
+}
+
+fn choose_partner(mut roster: ResMut<Roster>, mut events: EventReader<ChoosePartner>) {
+    for ChoosePartner(choice) in events.read() {
+        match choice {
+            Some(i) if *i >= roster.creatures.len() => {
+                warn!("Attempted to choose partner {i}, but the roster is too small!")
+            }
+            _ => roster.partner = *choice,
+        }
+    }
+}
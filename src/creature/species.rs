@@ -0,0 +1,207 @@
+use crate::creature::types::CreatureType;
+use crate::creature::{CaptureDecay, CaptureRequirements, FieldMove, FieldMoves, Species};
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, LoadContext};
+use bevy::prelude::*;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::time::{Duration, TryFromFloatSecsError};
+
+pub struct SpeciesPlugin;
+impl Plugin for SpeciesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<SpeciesList>()
+            .init_asset_loader::<SpeciesLoader>()
+            .init_resource::<KnownSpecies>()
+            .add_systems(Startup, load_species)
+            .add_systems(
+                Update,
+                (
+                    reload_species.run_if(on_event::<AssetEvent<SpeciesList>>),
+                    apply_species_to_new_creatures,
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// The stats shared by every creature of a species.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct SpeciesData {
+    pub capture_requirements: u32,
+
+    #[serde(default)]
+    pub creature_type: CreatureType,
+
+    /// Seconds after the last loop before capture progress starts to drain.
+    pub decay_delay: f32,
+
+    /// Seconds between each point of capture progress draining.
+    pub decay_interval: f32,
+
+    #[serde(default)]
+    pub field_moves: Vec<FieldMove>,
+
+    /// Asset path of the species' sprite.
+    #[serde(default = "default_sprite")]
+    pub sprite: String,
+
+    /// The identifiers of the LDtk levels the species can be found in.
+    #[serde(default)]
+    pub habitats: Vec<String>,
+}
+
+impl SpeciesData {
+    /// The decay delay and interval, which fail for seconds that are negative or too large.
+    fn decay_timings(&self) -> Result<(Duration, Duration), TryFromFloatSecsError> {
+        Ok((
+            Duration::try_from_secs_f32(self.decay_delay)?,
+            Duration::try_from_secs_f32(self.decay_interval)?,
+        ))
+    }
+}
+
+fn default_sprite() -> String {
+    "TempEnemy.png".into()
+}
+
+/// Every species, keyed by name, as read from a `.species.ron` file.
+#[derive(Asset, TypePath, Deserialize, Debug, Deref)]
+pub struct SpeciesList(HashMap<String, SpeciesData>);
+
+#[derive(Default)]
+struct SpeciesLoader;
+impl AssetLoader for SpeciesLoader {
+    type Asset = SpeciesList;
+    type Settings = ();
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = vec![];
+        reader.read_to_end(&mut bytes).await?;
+        Ok(ron::de::from_bytes(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["species.ron"]
+    }
+}
+
+/// The species data currently applied to creatures, kept so that reloads can report what changed.
+#[derive(Resource, Debug, Default)]
+pub(crate) struct KnownSpecies {
+    handle: Handle<SpeciesList>,
+    species: HashMap<String, SpeciesData>,
+}
+
+impl KnownSpecies {
+    fn get(&self, species: &Species) -> Option<&SpeciesData> {
+        self.species.get(&species.0)
+    }
+
+    /// Every species and its data, in no particular order.
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (&String, &SpeciesData)> {
+        self.species.iter()
+    }
+}
+
+/// Everything on a creature that comes from its species.
+type SpeciesStats = (
+    &'static Species,
+    &'static mut CaptureRequirements,
+    Option<&'static mut CaptureDecay>,
+    Option<&'static mut FieldMoves>,
+    Option<&'static mut CreatureType>,
+);
+
+fn load_species(mut known: ResMut<KnownSpecies>, asset_server: Res<AssetServer>) {
+    known.handle = asset_server.load("creatures.species.ron");
+}
+
+fn apply_species(
+    data: &SpeciesData,
+    requirements: &mut CaptureRequirements,
+    decay: Option<Mut<CaptureDecay>>,
+    field_moves: Option<Mut<FieldMoves>>,
+    creature_type: Option<Mut<CreatureType>>,
+) {
+    requirements.0 = data.capture_requirements;
+
+    if let Some(mut creature_type) = creature_type {
+        *creature_type = data.creature_type;
+    }
+
+    if let (Some(mut decay), Ok((delay, interval))) = (decay, data.decay_timings()) {
+        decay.delay = delay;
+        decay.timer.set_duration(interval);
+    }
+
+    if let Some(mut field_moves) = field_moves {
+        field_moves.0 = data.field_moves.clone();
+    }
+}
+
+fn reload_species(
+    mut events: EventReader<AssetEvent<SpeciesList>>,
+    mut known: ResMut<KnownSpecies>,
+    lists: Res<Assets<SpeciesList>>,
+    creatures: Query<SpeciesStats>,
+) {
+    let reloaded = events.read().any(|event| {
+        event.is_loaded_with_dependencies(&known.handle) || event.is_modified(&known.handle)
+    });
+    let Some(list) = reloaded.then(|| lists.get(&known.handle)).flatten() else {
+        return;
+    };
+
+    let first_load = known.species.is_empty();
+    let mut species = HashMap::new();
+    for (name, data) in list.iter() {
+        let old = known.species.get(name);
+        if let Err(e) = data.decay_timings() {
+            match old {
+                Some(old) => {
+                    warn!("Keeping the old data for species {name}, as its decay is invalid: {e}");
+                    species.insert(name.clone(), old.clone());
+                }
+                None => warn!("Skipping species {name}, as its decay is invalid: {e}"),
+            }
+            continue;
+        }
+
+        match old {
+            None if !first_load => info!("Species {name} added: {data:?}"),
+            Some(old) if old != data => info!("Species {name} changed: {old:?} -> {data:?}"),
+            _ => {}
+        }
+        species.insert(name.clone(), data.clone());
+    }
+    for name in known.species.keys() {
+        if !list.contains_key(name) {
+            info!("Species {name} removed");
+        }
+    }
+    known.species = species;
+
+    for (species, mut requirements, decay, field_moves, creature_type) in creatures {
+        if let Some(data) = known.get(species) {
+            apply_species(data, &mut requirements, decay, field_moves, creature_type);
+        }
+    }
+}
+
+fn apply_species_to_new_creatures(
+    known: Res<KnownSpecies>,
+    creatures: Query<SpeciesStats, Added<Species>>,
+) {
+    for (species, mut requirements, decay, field_moves, creature_type) in creatures {
+        if let Some(data) = known.get(species) {
+            apply_species(data, &mut requirements, decay, field_moves, creature_type);
+        }
+    }
+}
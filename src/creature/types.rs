@@ -1,6 +1,51 @@
 use bevy::prelude::*;
+use serde::Deserialize;
 
 pub struct TypesPlugin;
 impl Plugin for TypesPlugin {
-    fn build(&self, app: &mut App) {}
+    fn build(&self, app: &mut App) {
+        app.register_type::<CreatureType>();
+    }
+}
+
+/// The element of a creature, or of a styler, which decides how well loops work against it.
+#[derive(Component, Reflect, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[reflect(Component)]
+pub enum CreatureType {
+    #[default]
+    Normal,
+    Fire,
+    Water,
+    Grass,
+    Electric,
+}
+
+/// How much more progress loops make when their type is strong against the creature's.
+const SUPER_EFFECTIVE: f32 = 1.5;
+
+/// How much progress loops make when the creature's type is strong against theirs.
+const NOT_VERY_EFFECTIVE: f32 = 0.75;
+
+impl CreatureType {
+    /// Whether this type is strong against `other`.
+    pub fn beats(self, other: CreatureType) -> bool {
+        matches!(
+            (self, other),
+            (CreatureType::Fire, CreatureType::Grass)
+                | (CreatureType::Water, CreatureType::Fire)
+                | (CreatureType::Grass, CreatureType::Water)
+                | (CreatureType::Electric, CreatureType::Water)
+        )
+    }
+
+    /// The multiplier for something of this type acting on something of the `defender` type.
+    pub fn matchup(self, defender: CreatureType) -> f32 {
+        if self.beats(defender) {
+            SUPER_EFFECTIVE
+        } else if defender.beats(self) {
+            NOT_VERY_EFFECTIVE
+        } else {
+            1.
+        }
+    }
 }
@@ -0,0 +1,476 @@
+use crate::capture::session::{
+    set_session_state, CaptureSession, CapturedCreature, EndReason, SessionState,
+};
+use crate::capture::{Captured, Health, StylerEnergy, TakeDamage, MAX_HEALTH};
+use crate::creature::{creature_bundle, CaptureProgress, CaptureRequirements, Creature};
+use bevy::ecs::reflect::{ReflectComponent, ReflectResource};
+use bevy::input::common_conditions::input_just_pressed;
+use bevy::input::keyboard::{keyboard_input_system, Key, KeyboardInput};
+use bevy::input::InputSystem;
+use bevy::prelude::*;
+#[cfg(feature = "overworld")]
+use bevy_ecs_ldtk::LevelSelection;
+use std::collections::{HashMap, VecDeque};
+
+/// How many lines of output the console keeps.
+const MAX_LOG_LINES: usize = 12;
+
+const CONSOLE_BACKGROUND_COLOR: Color = Color::srgba(0., 0., 0., 0.8);
+
+const CONSOLE_FONT_SIZE: f32 = 8.;
+
+pub(super) struct ConsolePlugin;
+impl Plugin for ConsolePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Flags>()
+            .init_resource::<Console>()
+            .init_resource::<ConsoleCommands>()
+            .init_resource::<Flags>()
+            .add_console_command(SpawnCommand)
+            .add_console_command(HealCommand)
+            .add_console_command(DamageCommand)
+            .add_console_command(CaptureAllCommand)
+            .add_console_command(SetFlagCommand)
+            .add_console_command(StateCommand)
+            .add_systems(Startup, spawn_console)
+            // Part of reading input, so that nothing typed into the console reaches the game.
+            .add_systems(
+                PreUpdate,
+                (
+                    toggle_console.run_if(input_just_pressed(KeyCode::Backquote)),
+                    type_into_console.run_if(|console: Res<Console>| console.open),
+                )
+                    .chain()
+                    .in_set(InputSystem)
+                    .after(keyboard_input_system),
+            )
+            .add_systems(
+                Update,
+                (
+                    run_console_commands,
+                    update_console_text.run_if(resource_changed::<Console>),
+                )
+                    .chain(),
+            );
+
+        #[cfg(feature = "overworld")]
+        app.add_console_command(GotoLevelCommand);
+    }
+}
+
+/// A command that can be typed into the developer console.
+pub trait ConsoleCommand: Send + Sync + 'static {
+    /// The first word of the command.
+    fn name(&self) -> &'static str;
+
+    /// How the command is used, shown by `help`.
+    fn usage(&self) -> &'static str;
+
+    /// Runs the command with the words that came after its name, returning what to print.
+    fn run(&self, args: &[&str], world: &mut World) -> Result<String, String>;
+}
+
+/// Lets plugins add their own commands to the developer console.
+pub trait AddConsoleCommand {
+    fn add_console_command(&mut self, command: impl ConsoleCommand) -> &mut Self;
+}
+
+impl AddConsoleCommand for App {
+    fn add_console_command(&mut self, command: impl ConsoleCommand) -> &mut Self {
+        self.init_resource::<ConsoleCommands>();
+        self.world_mut()
+            .resource_mut::<ConsoleCommands>()
+            .0
+            .push(Box::new(command));
+        self
+    }
+}
+
+#[derive(Resource, Default)]
+struct ConsoleCommands(Vec<Box<dyn ConsoleCommand>>);
+
+#[derive(Resource, Debug, Default)]
+struct Console {
+    open: bool,
+    input: String,
+    log: VecDeque<String>,
+
+    /// Lines that have been entered but not run yet.
+    pending: Vec<String>,
+}
+
+impl Console {
+    fn print(&mut self, line: impl Into<String>) {
+        self.log.push_back(line.into());
+        while self.log.len() > MAX_LOG_LINES {
+            self.log.pop_front();
+        }
+    }
+}
+
+/// Flags set with `set-flag`, for trying out anything that checks them.
+#[derive(Resource, Reflect, Debug, Default, Deref)]
+#[reflect(Resource)]
+pub struct Flags(HashMap<String, String>);
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct ConsoleWindow;
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct ConsoleText;
+
+fn spawn_console(mut commands: Commands) {
+    commands.spawn((
+        Name::from("Console"),
+        ConsoleWindow,
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(0.),
+            width: Val::Percent(100.),
+            padding: UiRect::all(Val::Px(4.)),
+            ..default()
+        },
+        BackgroundColor(CONSOLE_BACKGROUND_COLOR),
+        GlobalZIndex(i32::MAX),
+        Visibility::Hidden,
+        children![(
+            ConsoleText,
+            Text::default(),
+            TextFont::from_font_size(CONSOLE_FONT_SIZE),
+        )],
+    ));
+}
+
+fn toggle_console(
+    mut console: ResMut<Console>,
+    window: Single<&mut Visibility, With<ConsoleWindow>>,
+) {
+    console.open = !console.open;
+    *window.into_inner() = if console.open {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    };
+}
+
+/// Types the keys pressed this frame, then lets go of every key so that the game doesn't see them.
+fn type_into_console(
+    mut console: ResMut<Console>,
+    mut keys: EventReader<KeyboardInput>,
+    mut input: ResMut<ButtonInput<KeyCode>>,
+) {
+    input.reset_all();
+    for key in keys.read() {
+        if !key.state.is_pressed() || key.key_code == KeyCode::Backquote {
+            continue;
+        }
+
+        match &key.logical_key {
+            Key::Character(text) => console.input.push_str(text),
+            Key::Space => console.input.push(' '),
+            Key::Backspace => {
+                console.input.pop();
+            }
+            Key::Enter => {
+                let line = std::mem::take(&mut console.input);
+                if !line.trim().is_empty() {
+                    console.print(format!("> {line}"));
+                    console.pending.push(line);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn run_console_commands(world: &mut World) {
+    let pending = std::mem::take(&mut world.resource_mut::<Console>().pending);
+    if pending.is_empty() {
+        return;
+    }
+
+    world.resource_scope(|world, commands: Mut<ConsoleCommands>| {
+        for line in pending {
+            let words = line.split_whitespace().collect::<Vec<_>>();
+            let Some((name, args)) = words.split_first() else {
+                continue;
+            };
+
+            let output = match commands.0.iter().find(|command| command.name() == *name) {
+                Some(command) => command
+                    .run(args, world)
+                    .unwrap_or_else(|e| format!("Error: {e}")),
+                None if *name == "help" => commands
+                    .0
+                    .iter()
+                    .map(|command| command.usage())
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+                None => format!("Unknown command '{name}', try 'help'"),
+            };
+
+            let mut console = world.resource_mut::<Console>();
+            for line in output.lines() {
+                console.print(line);
+            }
+        }
+    });
+}
+
+fn update_console_text(console: Res<Console>, text: Single<&mut Text, With<ConsoleText>>) {
+    let mut text = text.into_inner();
+    text.0.clear();
+    for line in &console.log {
+        text.0.push_str(line);
+        text.0.push('\n');
+    }
+    text.0.push_str("> ");
+    text.0.push_str(&console.input);
+}
+
+/// The capture session that hasn't ended yet, if there is one.
+fn active_session(world: &mut World) -> Option<Entity> {
+    world
+        .query::<(Entity, &CaptureSession)>()
+        .iter(world)
+        .find(|(_, session)| !matches!(session.state, SessionState::Ended { .. }))
+        .map(|(entity, _)| entity)
+}
+
+fn parse<T: std::str::FromStr>(value: &str, what: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("'{value}' is not a valid {what}"))
+}
+
+struct SpawnCommand;
+impl ConsoleCommand for SpawnCommand {
+    fn name(&self) -> &'static str {
+        "spawn"
+    }
+
+    fn usage(&self) -> &'static str {
+        "spawn <species> [x y]"
+    }
+
+    fn run(&self, args: &[&str], world: &mut World) -> Result<String, String> {
+        let position = match args {
+            [_] => Vec2::ZERO,
+            [_, x, y] => Vec2::new(parse(x, "number")?, parse(y, "number")?),
+            _ => return Err(self.usage().into()),
+        };
+
+        let icon = world.resource::<AssetServer>().load("TempEnemy.png");
+        world.spawn((
+            creature_bundle(args[0], icon),
+            Transform::from_translation(position.extend(0.)),
+        ));
+        Ok(format!("Spawned {} at {position}", args[0]))
+    }
+}
+
+struct HealCommand;
+impl ConsoleCommand for HealCommand {
+    fn name(&self) -> &'static str {
+        "heal"
+    }
+
+    fn usage(&self) -> &'static str {
+        "heal"
+    }
+
+    fn run(&self, _args: &[&str], world: &mut World) -> Result<String, String> {
+        let mut stylers = world.query::<(&mut Health, Option<&mut StylerEnergy>)>();
+        for (mut health, energy) in stylers.iter_mut(world) {
+            health.0 = MAX_HEALTH;
+            if let Some(mut energy) = energy {
+                energy.current = energy.max;
+            }
+        }
+        Ok("Healed".into())
+    }
+}
+
+struct DamageCommand;
+impl ConsoleCommand for DamageCommand {
+    fn name(&self) -> &'static str {
+        "damage"
+    }
+
+    fn usage(&self) -> &'static str {
+        "damage <n>"
+    }
+
+    fn run(&self, args: &[&str], world: &mut World) -> Result<String, String> {
+        let [amount] = args else {
+            return Err(self.usage().into());
+        };
+        let amount = parse(amount, "amount")?;
+
+        let Some(session) = active_session(world) else {
+            return Err("Nothing is being captured".into());
+        };
+
+        world.trigger(TakeDamage { session, amount });
+        Ok(format!("Took {amount} damage"))
+    }
+}
+
+struct CaptureAllCommand;
+impl ConsoleCommand for CaptureAllCommand {
+    fn name(&self) -> &'static str {
+        "capture-all"
+    }
+
+    fn usage(&self) -> &'static str {
+        "capture-all"
+    }
+
+    fn run(&self, _args: &[&str], world: &mut World) -> Result<String, String> {
+        let mut creatures = world.query_filtered::<(Entity, &mut CaptureProgress, &CaptureRequirements), (With<Creature>, Without<Captured>)>();
+        let captured = creatures
+            .iter_mut(world)
+            .map(|(entity, mut progress, requirements)| {
+                progress.progress = requirements.0;
+                entity
+            })
+            .collect::<Vec<_>>();
+
+        if captured.is_empty() {
+            return Ok("There was nothing to capture".into());
+        }
+
+        for &entity in &captured {
+            world.entity_mut(entity).insert(Captured);
+        }
+
+        // Capturing ends the session being drawn, or a new one if nothing is being captured.
+        let session = active_session(world).unwrap_or_else(|| {
+            world
+                .spawn(CaptureSession {
+                    state: SessionState::Started,
+                })
+                .id()
+        });
+        let reason = EndReason::Success {
+            captured: captured
+                .iter()
+                .map(|&creature| CapturedCreature {
+                    creature,
+                    overshot_by: 0,
+                })
+                .collect(),
+            failed: vec![],
+        };
+        set_session_state(
+            &mut world.commands(),
+            session,
+            SessionState::Ended { reason },
+        );
+        world.flush();
+        Ok(format!("Captured {} creatures", captured.len()))
+    }
+}
+
+#[cfg(feature = "overworld")]
+struct GotoLevelCommand;
+#[cfg(feature = "overworld")]
+impl ConsoleCommand for GotoLevelCommand {
+    fn name(&self) -> &'static str {
+        "goto-level"
+    }
+
+    fn usage(&self) -> &'static str {
+        "goto-level <id|index>"
+    }
+
+    fn run(&self, args: &[&str], world: &mut World) -> Result<String, String> {
+        let [level] = args else {
+            return Err(self.usage().into());
+        };
+
+        let selection = match level.parse::<usize>() {
+            Ok(index) => LevelSelection::index(index),
+            Err(_) => LevelSelection::Identifier(level.to_string()),
+        };
+        world.insert_resource(selection);
+        Ok(format!("Going to level {level}"))
+    }
+}
+
+struct SetFlagCommand;
+impl ConsoleCommand for SetFlagCommand {
+    fn name(&self) -> &'static str {
+        "set-flag"
+    }
+
+    fn usage(&self) -> &'static str {
+        "set-flag <name> [value]"
+    }
+
+    fn run(&self, args: &[&str], world: &mut World) -> Result<String, String> {
+        let (name, value) = match args {
+            [name] => (name, "true"),
+            [name, value] => (name, *value),
+            _ => return Err(self.usage().into()),
+        };
+
+        world
+            .resource_mut::<Flags>()
+            .0
+            .insert(name.to_string(), value.to_string());
+        Ok(format!("{name} = {value}"))
+    }
+}
+
+/// Prints the reflected components of a named entity, or a resource with the given type name.
+struct StateCommand;
+impl ConsoleCommand for StateCommand {
+    fn name(&self) -> &'static str {
+        "state"
+    }
+
+    fn usage(&self) -> &'static str {
+        "state <entity name|resource>"
+    }
+
+    fn run(&self, args: &[&str], world: &mut World) -> Result<String, String> {
+        let [name] = args else {
+            return Err(self.usage().into());
+        };
+
+        let registry = world.resource::<AppTypeRegistry>().clone();
+        let registry = registry.read();
+
+        let mut named = world.query::<(Entity, &Name)>();
+        if let Some((entity, _)) = named
+            .iter(world)
+            .find(|(_, entity_name)| entity_name.as_str() == *name)
+        {
+            let entity_ref = world.entity(entity);
+            let components = world
+                .inspect_entity(entity)
+                .map_err(|e| e.to_string())?
+                .filter_map(|info| {
+                    let registration = registry.get(info.type_id()?)?;
+                    let component = registration
+                        .data::<ReflectComponent>()?
+                        .reflect(entity_ref)?;
+                    Some(format!("{component:?}"))
+                })
+                .collect::<Vec<_>>();
+            return Ok(format!("{name} ({entity})\n{}", components.join("\n")));
+        }
+
+        let resource = registry
+            .iter()
+            .find(|registration| registration.type_info().type_path_table().short_path() == *name)
+            .and_then(|registration| registration.data::<ReflectResource>())
+            .and_then(|resource| resource.reflect(&*world).ok())
+            .map(|resource| format!("{resource:?}"))
+            .ok_or_else(|| format!("No entity or resource named '{name}'"));
+        resource
+    }
+}
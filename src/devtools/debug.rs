@@ -1,6 +1,203 @@
+use crate::capture::config::CaptureConfig;
+use crate::capture::CaptureLine;
+use crate::creature::Creature;
+use avian2d::prelude::Collider;
+use bevy::color::palettes::css::{AQUA, LIME, MAGENTA, RED, WHITE, YELLOW};
 use bevy::prelude::*;
+use bevy::render::view::RenderLayers;
+
+/// The layer the gizmo camera renders.
+const GIZMO_LAYER: usize = 1;
+
+const LABEL_FONT_SIZE: f32 = 8.;
 
 pub(super) struct DebugTools;
 impl Plugin for DebugTools {
-    fn build(&self, app: &mut App) {}
+    fn build(&self, app: &mut App) {
+        app.init_gizmo_group::<CaptureDebugGizmos>()
+            .register_type::<DebugOverlays>()
+            .init_resource::<DebugOverlays>()
+            .add_systems(Startup, configure_gizmos)
+            .add_systems(Update, (toggle_overlays, clear_intersection_labels))
+            .add_systems(
+                Update,
+                (
+                    draw_line_collider.run_if(|overlays: Res<DebugOverlays>| overlays.collider),
+                    draw_intersections
+                        .after(clear_intersection_labels)
+                        .run_if(|overlays: Res<DebugOverlays>| overlays.intersections),
+                    draw_enclosed_area
+                        .run_if(|overlays: Res<DebugOverlays>| overlays.enclosed_area),
+                    draw_width_offsets
+                        .run_if(|overlays: Res<DebugOverlays>| overlays.width_offsets),
+                )
+                    .run_if(any_with_component::<CaptureLine>),
+            )
+            .add_systems(
+                Update,
+                draw_creatures.run_if(|overlays: Res<DebugOverlays>| overlays.creatures),
+            );
+    }
+}
+
+#[derive(Default, Reflect, GizmoConfigGroup)]
+struct CaptureDebugGizmos;
+
+/// Which of the capture debug overlays are shown. Each is toggled with a function key.
+#[derive(Resource, Reflect, Debug, Default)]
+#[reflect(Resource)]
+struct DebugOverlays {
+    /// F1: the polyline collider of the capture line.
+    collider: bool,
+
+    /// F2: where the line crosses itself, labelled with the indices of the segments that cross.
+    intersections: bool,
+
+    /// F3: the convex parts of the area used to decide which creatures were looped.
+    enclosed_area: bool,
+
+    /// F4: creature centers, coloured by whether they are inside the loop.
+    creatures: bool,
+
+    /// F5: the segments offset by half the line's width that are also checked for crossings.
+    width_offsets: bool,
+}
+
+/// Text showing the segment indices of an intersection. Replaced every frame.
+#[derive(Component)]
+struct IntersectionLabel;
+
+fn configure_gizmos(mut config_store: ResMut<GizmoConfigStore>) {
+    let (config, _) = config_store.config_mut::<CaptureDebugGizmos>();
+    config.render_layers = RenderLayers::layer(GIZMO_LAYER);
+    config.line.width = 1.;
+}
+
+fn toggle_overlays(mut overlays: ResMut<DebugOverlays>, input: Res<ButtonInput<KeyCode>>) {
+    let overlays = overlays.as_mut();
+    let toggles = [
+        (KeyCode::F1, &mut overlays.collider, "line collider"),
+        (KeyCode::F2, &mut overlays.intersections, "intersections"),
+        (KeyCode::F3, &mut overlays.enclosed_area, "enclosed area"),
+        (KeyCode::F4, &mut overlays.creatures, "creature containment"),
+        (KeyCode::F5, &mut overlays.width_offsets, "width offsets"),
+    ];
+
+    for (key, enabled, name) in toggles {
+        if input.just_pressed(key) {
+            *enabled = !*enabled;
+            info!(
+                "Debug overlay for {name}: {}",
+                if *enabled { "on" } else { "off" }
+            );
+        }
+    }
+}
+
+fn draw_line_collider(
+    mut gizmos: Gizmos<CaptureDebugGizmos>,
+    line: Single<&Collider, With<CaptureLine>>,
+) {
+    let Some(polyline) = line.shape().as_polyline() else {
+        return;
+    };
+
+    let vertices = polyline.vertices();
+    for [start, end] in polyline.indices() {
+        let start = vertices[*start as usize];
+        let end = vertices[*end as usize];
+        gizmos.line_2d(Vec2::new(start.x, start.y), Vec2::new(end.x, end.y), AQUA);
+    }
+}
+
+fn draw_intersections(
+    mut commands: Commands,
+    mut gizmos: Gizmos<CaptureDebugGizmos>,
+    line: Single<&CaptureLine>,
+) {
+    for intersection in line.intersections() {
+        gizmos.circle_2d(intersection.at, 2., RED);
+        commands.spawn((
+            IntersectionLabel,
+            Text2d::new(format!("{}/{}", intersection.segment, intersection.other)),
+            TextFont::from_font_size(LABEL_FONT_SIZE),
+            TextColor(RED.into()),
+            Transform::from_translation((intersection.at + Vec2::new(0., 6.)).extend(10.)),
+            RenderLayers::layer(GIZMO_LAYER),
+        ));
+    }
+}
+
+fn draw_enclosed_area(
+    mut gizmos: Gizmos<CaptureDebugGizmos>,
+    line: Single<(&CaptureLine, &Collider)>,
+) {
+    let (line, collider) = line.into_inner();
+    let Some(area) = line.enclosed_area(collider) else {
+        return;
+    };
+    let Some(compound) = area.shape().as_compound() else {
+        return;
+    };
+
+    for (isometry, shape) in compound.shapes() {
+        let Some(polygon) = shape.as_convex_polygon() else {
+            continue;
+        };
+
+        let mut points = polygon
+            .points()
+            .iter()
+            .map(|point| {
+                let point = isometry * point;
+                Vec2::new(point.x, point.y)
+            })
+            .collect::<Vec<_>>();
+        if let Some(first) = points.first().copied() {
+            points.push(first);
+        }
+        gizmos.linestrip_2d(points, MAGENTA);
+    }
+}
+
+fn draw_creatures(
+    mut gizmos: Gizmos<CaptureDebugGizmos>,
+    config: Res<CaptureConfig>,
+    line: Option<Single<(&CaptureLine, &Collider)>>,
+    creatures: Query<(&Transform, Option<&Collider>), With<Creature>>,
+) {
+    let area = line.and_then(|line| {
+        let (line, collider) = line.into_inner();
+        line.enclosed_area(collider)
+    });
+
+    for (transform, collider) in creatures {
+        let center = transform.translation.xy();
+        let contained = area
+            .as_ref()
+            .is_some_and(|area| config.containment.contains(area, center, collider));
+
+        gizmos.cross_2d(
+            Isometry2d::from_translation(center),
+            6.,
+            if contained { LIME } else { WHITE },
+        );
+    }
+}
+
+fn draw_width_offsets(mut gizmos: Gizmos<CaptureDebugGizmos>, line: Single<&CaptureLine>) {
+    for segment in line.line.iter().zip(line.line.iter().skip(1)) {
+        for (start, end) in line.width_offsets(segment) {
+            gizmos.line_2d(start, end, YELLOW);
+        }
+    }
+}
+
+fn clear_intersection_labels(
+    mut commands: Commands,
+    labels: Query<Entity, With<IntersectionLabel>>,
+) {
+    for label in labels {
+        commands.entity(label).despawn();
+    }
 }
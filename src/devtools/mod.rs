@@ -1,5 +1,7 @@
+mod console;
 mod debug;
 
+use crate::devtools::console::ConsolePlugin;
 use crate::devtools::debug::DebugTools;
 use bevy::prelude::*;
 
@@ -13,6 +15,8 @@ impl Plugin for Devtools {
         })
         .add_plugins(bevy_inspector_egui::quick::WorldInspectorPlugin::new());
 
+        app.add_plugins(ConsolePlugin);
+
         #[cfg(debug_assertions)]
         app.add_plugins(DebugTools);
     }
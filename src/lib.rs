@@ -1,18 +1,43 @@
 extern crate core;
 
 mod camera;
-mod capture;
-mod creature;
+pub mod capture;
+pub mod creature;
+#[cfg(feature = "devtools")]
 mod devtools;
-mod movement;
+pub mod movement;
+#[cfg(feature = "overworld")]
 mod overworld;
+mod pause;
+mod save;
+mod settings;
+mod sound;
 mod ui;
 
 use crate::camera::BevymonCameraPlugin;
+#[cfg(not(feature = "overworld"))]
+use crate::capture::config::CaptureConfig;
+use crate::capture::score::BestScores;
+use crate::capture::styler::menu::StylerMenuPlugin;
+#[cfg(not(feature = "overworld"))]
+use crate::capture::styler::Styler;
+use crate::capture::styler::StylerProgress;
+use crate::creature::CreaturePlugin;
 use crate::movement::MovementPlugin;
+#[cfg(feature = "overworld")]
 use crate::overworld::OverworldPlugin;
+use crate::pause::PausePlugin;
+#[cfg(feature = "devtools")]
+use crate::save::save_path;
+use crate::save::SavePlugin;
+use crate::settings::SettingsPlugin;
+use crate::sound::SoundPlugin;
 use bevy::prelude::*;
 
+pub use crate::capture::CaptureCorePlugin;
+pub use crate::save::SaveFile;
+pub use crate::settings::Settings;
+
 #[derive(Component, Reflect, Debug)]
 #[reflect(Component)]
 struct Despawn;
@@ -20,17 +45,46 @@ struct Despawn;
 pub struct BevymonRangerPlugin;
 impl Plugin for BevymonRangerPlugin {
     fn build(&self, app: &mut App) {
+        let capture = CaptureCorePlugin::default();
+        // Recordings are for replaying bugs while developing, so players don't collect them.
+        #[cfg(feature = "devtools")]
+        let capture = capture.with_recording_directory(save_path("recordings"));
+
         app.add_plugins(avian2d::PhysicsPlugins::default())
             .add_plugins(BevymonCameraPlugin)
-            .add_plugins(OverworldPlugin)
             .add_plugins(MovementPlugin)
-            .add_systems(Last, despawn_entities);
+            .add_plugins(SettingsPlugin)
+            .add_plugins(DespawnPlugin)
+            // Loaded before CaptureCorePlugin starts these from nothing. Saving is left to the game,
+            // so that embedding capturing doesn't touch the player's saves.
+            .add_plugins(SavePlugin::<StylerProgress>::default())
+            .add_plugins(SavePlugin::<BestScores>::default())
+            .add_plugins(capture)
+            .add_plugins(StylerMenuPlugin)
+            .add_plugins(CreaturePlugin)
+            .add_plugins(PausePlugin)
+            .add_plugins(SoundPlugin);
+
+        #[cfg(feature = "overworld")]
+        app.add_plugins(OverworldPlugin);
+
+        // Without the overworld there's no player to hold the styler, so it's held on its own.
+        #[cfg(not(feature = "overworld"))]
+        app.add_systems(Startup, spawn_styler);
 
         #[cfg(feature = "devtools")]
         app.add_plugins(devtools::Devtools);
     }
 }
 
+/// Despawns everything marked with [`Despawn`] at the end of each frame.
+pub(crate) struct DespawnPlugin;
+impl Plugin for DespawnPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Last, despawn_entities);
+    }
+}
+
 #[derive(Component, Reflect, Debug)]
 #[reflect(Component)]
 #[relationship(relationship_target = DespawnChildren)]
@@ -40,6 +94,12 @@ pub(crate) struct DespawnWith(Entity);
 #[reflect(Component)]
 #[relationship_target(relationship = DespawnWith, linked_spawn)]
 pub(crate) struct DespawnChildren(Vec<Entity>);
+
+#[cfg(not(feature = "overworld"))]
+fn spawn_styler(mut commands: Commands, config: Res<CaptureConfig>) {
+    commands.spawn((Name::from("Styler"), Styler::base(&config)));
+}
+
 fn despawn_entities(mut commands: Commands, entities: Query<Entity, With<Despawn>>) {
     for entity in entities {
         commands.entity(entity).despawn_related::<DespawnChildren>();
@@ -1,29 +1,32 @@
-use bevy::log::LogPlugin;
 use bevy::prelude::*;
 use bevymon_ranger::*;
 
 fn main() {
-    App::new()
-        .add_plugins(
-            DefaultPlugins
-                .set(WindowPlugin {
-                    primary_window: Some(Window {
-                        title: "bevymon_ranger".into(),
-                        name: Some("bevymon_ranger".into()),
+    // The settings decide how logging is set up, so any problem reading them is logged afterwards.
+    let (settings, load_error) = match Settings::read() {
+        Ok(settings) => (settings, None),
+        Err(e) => (Settings::default(), Some(e)),
+    };
 
-                        // This allows i3wm to force it into floating.
-                        resizable: false,
-                        ..default()
-                    }),
-                    ..default()
-                })
-                .set(ImagePlugin::default_nearest())
-                .set(LogPlugin {
-                    filter: "info,wgpu_core=off,wgpu_hal=off,mygame=debug".into(),
-                    level: bevy::log::Level::DEBUG,
-                    ..default()
-                }),
-        )
+    let mut app = App::new();
+    app.add_plugins(
+        DefaultPlugins
+            .set(WindowPlugin {
+                primary_window: Some(settings.window()),
+                ..default()
+            })
+            .set(ImagePlugin::default_nearest())
+            .set(settings.log_plugin()),
+    );
+
+    if let Some(e) = load_error {
+        warn!(
+            "Unable to read settings from {}: {e}",
+            Settings::path().display()
+        );
+    }
+
+    app.insert_resource(settings)
         .add_plugins(BevymonRangerPlugin)
         .run();
 }
@@ -1,6 +1,14 @@
+mod hud;
+mod obstacle;
+mod partner;
 mod player;
+mod reload;
 
+use crate::overworld::hud::HudPlugin;
+use crate::overworld::obstacle::ObstaclePlugin;
+use crate::overworld::partner::PartnerPlugin;
 use crate::overworld::player::PlayerPlugin;
+use crate::overworld::reload::ReloadPlugin;
 use bevy::prelude::*;
 use bevy_ecs_ldtk::{LdtkPlugin, LdtkWorldBundle, LevelSelection};
 
@@ -9,6 +17,10 @@ impl Plugin for OverworldPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins(LdtkPlugin)
             .add_plugins(PlayerPlugin)
+            .add_plugins(PartnerPlugin)
+            .add_plugins(ObstaclePlugin)
+            .add_plugins(ReloadPlugin)
+            .add_plugins(HudPlugin)
             .insert_resource(LevelSelection::index(0))
             .add_systems(Startup, ldtk_setup);
     }
@@ -0,0 +1,497 @@
+use crate::capture::StylerEnergy;
+use crate::creature::Creature;
+use crate::overworld::player::Player;
+use crate::settings::Settings;
+use crate::DespawnWith;
+use bevy::asset::RenderAssetUsages;
+use bevy::image::ImageSampler;
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use bevy_ecs_ldtk::ldtk::{LayerDefinition, Level, Type};
+use bevy_ecs_ldtk::prelude::*;
+
+/// How many pixels of the level each pixel of the minimap covers.
+const MINIMAP_SCALE: i32 = 16;
+
+/// How wide the minimap is on screen, with its height following the level's shape.
+const MINIMAP_WIDTH: f32 = 64.;
+
+const ENERGY_HEIGHT: f32 = 4.;
+
+/// The fraction of styler energy below which the indicator warns the player.
+const LOW_ENERGY: f32 = 0.25;
+
+const HUD_FONT_SIZE: f32 = 8.;
+
+/// The minimap colour of cells covered by a tile layer but no IntGrid value.
+const FLOOR_COLOR: Color = Color::srgba(0.45, 0.42, 0.36, 0.9);
+
+/// Shows the area name, a minimap of the current level and the styler's energy while exploring.
+pub(super) struct HudPlugin;
+impl Plugin for HudPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<MinimapMarker>()
+            .register_type::<Minimap>()
+            .add_systems(Startup, spawn_hud)
+            .add_systems(
+                Update,
+                (
+                    (mark_ldtk_entities, mark::<Player>, mark::<Creature>),
+                    draw_minimap.run_if(on_event::<LevelEvent>),
+                    spawn_minimap_icons,
+                    move_minimap_icons,
+                )
+                    .chain(),
+            )
+            .add_systems(Update, update_energy_indicator)
+            .add_systems(Update, apply_palette.run_if(resource_changed::<Settings>));
+    }
+}
+
+/// Something shown on the minimap.
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+#[reflect(Component)]
+pub enum MinimapMarker {
+    Player,
+    Npc,
+    Creature,
+    Door,
+}
+
+impl MinimapMarker {
+    /// Reads the marker for an LDtk entity from its identifier.
+    fn from_ldtk(identifier: &str) -> Option<Self> {
+        match identifier {
+            "Npc" => Some(MinimapMarker::Npc),
+            "Door" => Some(MinimapMarker::Door),
+            _ => None,
+        }
+    }
+
+    fn color(self) -> Color {
+        match self {
+            MinimapMarker::Player => Color::WHITE,
+            MinimapMarker::Npc => Color::srgb(0.34, 0.71, 0.91),
+            MinimapMarker::Creature => Color::srgb(0.9, 0.62, 0.),
+            MinimapMarker::Door => Color::srgb(0.94, 0.89, 0.26),
+        }
+    }
+
+    /// The size of the marker's icon on the minimap, in pixels.
+    fn size(self) -> f32 {
+        match self {
+            MinimapMarker::Player => 4.,
+            MinimapMarker::Door => 3.,
+            MinimapMarker::Npc | MinimapMarker::Creature => 2.,
+        }
+    }
+}
+
+/// Gives every entity with a `T` the minimap marker for it.
+trait Marked: Component {
+    const MARKER: MinimapMarker;
+}
+
+impl Marked for Player {
+    const MARKER: MinimapMarker = MinimapMarker::Player;
+}
+
+impl Marked for Creature {
+    const MARKER: MinimapMarker = MinimapMarker::Creature;
+}
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct Hud;
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct AreaName;
+
+/// The map of the current level, which markers are placed over.
+#[derive(Component, Reflect, Debug, Default)]
+#[reflect(Component)]
+struct Minimap {
+    level: Option<LevelIid>,
+
+    /// The size of the level in world units.
+    size: Vec2,
+}
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct MinimapIcon;
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct EnergyIndicator;
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct EnergyFill;
+
+fn spawn_hud(mut commands: Commands, settings: Res<Settings>) {
+    let palette = settings.palette.colors();
+    commands.spawn((
+        Name::from("HUD"),
+        Hud,
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(4.),
+            right: Val::Px(4.),
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::End,
+            row_gap: Val::Px(2.),
+            padding: UiRect::all(Val::Px(2.)),
+            ..default()
+        },
+        BackgroundColor(palette.panel),
+        children![
+            (
+                AreaName,
+                Text::default(),
+                TextFont::from_font_size(HUD_FONT_SIZE),
+                TextColor(palette.text),
+            ),
+            (
+                Minimap::default(),
+                ImageNode::default(),
+                Node {
+                    width: Val::Px(MINIMAP_WIDTH),
+                    height: Val::Px(MINIMAP_WIDTH),
+                    ..default()
+                },
+            ),
+            (
+                EnergyIndicator,
+                Node {
+                    width: Val::Px(MINIMAP_WIDTH),
+                    height: Val::Px(ENERGY_HEIGHT),
+                    ..default()
+                },
+                BackgroundColor(palette.clear),
+                Visibility::Hidden,
+                children![(
+                    EnergyFill,
+                    Node {
+                        width: Val::Percent(100.),
+                        height: Val::Percent(100.),
+                        ..default()
+                    },
+                    BackgroundColor(palette.success),
+                )],
+            ),
+        ],
+    ));
+}
+
+fn mark<T: Marked>(mut commands: Commands, added: Query<Entity, Added<T>>) {
+    for entity in added {
+        commands.entity(entity).insert(T::MARKER);
+    }
+}
+
+fn mark_ldtk_entities(
+    mut commands: Commands,
+    added: Query<(Entity, &EntityInstance), Added<EntityInstance>>,
+) {
+    for (entity, instance) in added {
+        if let Some(marker) = MinimapMarker::from_ldtk(&instance.identifier) {
+            commands.entity(entity).insert(marker);
+        }
+    }
+}
+
+/// The name shown for a level, from its `Name` field if it has one.
+fn area_name(level: &Level) -> String {
+    match level.get_maybe_string_field("Name") {
+        Ok(Some(name)) => name.clone(),
+        _ => level.identifier.clone(),
+    }
+}
+
+/// Draws one pixel per cell of the level, from its tile and IntGrid layers, colouring IntGrid
+/// values as the project's layer `definitions` do.
+fn minimap_image(level: &Level, definitions: &[LayerDefinition]) -> Image {
+    let width = (level.px_wid / MINIMAP_SCALE).max(1) as u32;
+    let height = (level.px_hei / MINIMAP_SCALE).max(1) as u32;
+    let mut image = Image::new_fill(
+        Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &[0, 0, 0, 0],
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    );
+    image.sampler = ImageSampler::nearest();
+
+    let mut paint = |px: IVec2, color: Color| {
+        let cell = px / MINIMAP_SCALE;
+        if cell.x >= 0 && cell.y >= 0 {
+            // Pixels outside of the image are just left off of the map.
+            let _ = image.set_color_at(cell.x as u32, cell.y as u32, color);
+        }
+    };
+
+    // LDtk lists layers from the top down, so they are drawn in reverse to end up on top.
+    let layers = level.layer_instances.iter().flatten().rev();
+    for layer in layers {
+        match layer.layer_instance_type {
+            Type::Tiles | Type::AutoLayer => {
+                for tile in layer.grid_tiles.iter().chain(&layer.auto_layer_tiles) {
+                    paint(tile.px, FLOOR_COLOR);
+                }
+            }
+            Type::IntGrid => {
+                let Some(definition) = definitions
+                    .iter()
+                    .find(|definition| definition.uid == layer.layer_def_uid)
+                else {
+                    continue;
+                };
+
+                for (i, value) in layer.int_grid_csv.iter().enumerate() {
+                    let Some(value) = definition
+                        .int_grid_values
+                        .iter()
+                        .find(|definition| definition.value == *value)
+                    else {
+                        continue;
+                    };
+
+                    let i = i as i32;
+                    let cell = IVec2::new(i % layer.c_wid, i / layer.c_wid);
+                    paint(cell * layer.grid_size, value.color);
+                }
+            }
+            Type::Entities => {}
+        }
+    }
+
+    image
+}
+
+fn draw_minimap(
+    mut events: EventReader<LevelEvent>,
+    projects: Res<Assets<LdtkProject>>,
+    project: Single<&LdtkProjectHandle>,
+    mut images: ResMut<Assets<Image>>,
+    minimap: Single<(&mut Minimap, &mut ImageNode, &mut Node)>,
+    area_name_text: Single<&mut Text, With<AreaName>>,
+) {
+    let Some(project) = projects.get(&project.handle) else {
+        return;
+    };
+
+    let Some((iid, level)) = events
+        .read()
+        .filter_map(|event| match event {
+            LevelEvent::Spawned(iid) => {
+                Some((iid, project.get_raw_level_by_iid(&iid.to_string())?))
+            }
+            _ => None,
+        })
+        .last()
+    else {
+        return;
+    };
+
+    let (mut minimap, mut image, mut node) = minimap.into_inner();
+    minimap.level = Some(iid.clone());
+    minimap.size = Vec2::new(level.px_wid as f32, level.px_hei as f32);
+    image.image = images.add(minimap_image(level, &project.json_data().defs.layers));
+    node.height = Val::Px(MINIMAP_WIDTH * minimap.size.y / minimap.size.x.max(1.));
+
+    area_name_text.into_inner().0 = area_name(level);
+}
+
+fn spawn_minimap_icons(
+    mut commands: Commands,
+    minimap: Single<Entity, With<Minimap>>,
+    added: Query<(Entity, &MinimapMarker), Added<MinimapMarker>>,
+) {
+    let minimap = minimap.into_inner();
+    for (entity, marker) in added {
+        commands.spawn((
+            MinimapIcon,
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Px(marker.size()),
+                height: Val::Px(marker.size()),
+                // Centres the icon on the position it is placed at.
+                margin: UiRect {
+                    left: Val::Px(-marker.size() / 2.),
+                    top: Val::Px(-marker.size() / 2.),
+                    ..default()
+                },
+                ..default()
+            },
+            BackgroundColor(marker.color()),
+            Visibility::Hidden,
+            ChildOf(minimap),
+            DespawnWith(entity),
+        ));
+    }
+}
+
+/// Places each icon where its entity is in the current level, hiding it if it is somewhere else.
+fn move_minimap_icons(
+    minimap: Single<&Minimap>,
+    levels: Query<(&LevelIid, &GlobalTransform)>,
+    targets: Query<&GlobalTransform, With<MinimapMarker>>,
+    icons: Query<(&DespawnWith, &mut Node, &mut Visibility), With<MinimapIcon>>,
+) {
+    let minimap = minimap.into_inner();
+    let origin = levels
+        .iter()
+        .find(|(iid, _)| Some(*iid) == minimap.level.as_ref())
+        .map(|(_, transform)| transform.translation().xy());
+
+    for (target, mut node, mut visibility) in icons {
+        // Levels are placed by their bottom left corner, while UI goes down from the top.
+        let fraction = origin
+            .zip(targets.get(target.0).ok())
+            .map(|(origin, target)| (target.translation().xy() - origin) / minimap.size)
+            .filter(|fraction| {
+                (0. ..=1.).contains(&fraction.x) && (0. ..=1.).contains(&fraction.y)
+            });
+
+        let Some(fraction) = fraction else {
+            *visibility = Visibility::Hidden;
+            continue;
+        };
+
+        node.left = Val::Percent(fraction.x * 100.);
+        node.top = Val::Percent((1. - fraction.y) * 100.);
+        *visibility = Visibility::Inherited;
+    }
+}
+
+fn update_energy_indicator(
+    energy: Option<Single<&StylerEnergy>>,
+    settings: Res<Settings>,
+    indicator: Single<&mut Visibility, With<EnergyIndicator>>,
+    fill: Single<(&mut Node, &mut BackgroundColor), With<EnergyFill>>,
+) {
+    let mut visibility = indicator.into_inner();
+    let Some(energy) = energy else {
+        *visibility = Visibility::Hidden;
+        return;
+    };
+
+    let fraction = (energy.current / energy.max.max(f32::EPSILON)).clamp(0., 1.);
+    let palette = settings.palette.colors();
+    let (mut node, mut background) = fill.into_inner();
+    *visibility = Visibility::Inherited;
+    node.width = Val::Percent(fraction * 100.);
+    background.0 = if fraction < LOW_ENERGY {
+        palette.warning
+    } else {
+        palette.success
+    };
+}
+
+fn apply_palette(
+    settings: Res<Settings>,
+    hud: Single<&mut BackgroundColor, (With<Hud>, Without<EnergyIndicator>)>,
+    indicator: Single<&mut BackgroundColor, (With<EnergyIndicator>, Without<Hud>)>,
+    area_name_text: Single<&mut TextColor, With<AreaName>>,
+) {
+    let palette = settings.palette.colors();
+    hud.into_inner().0 = palette.panel;
+    indicator.into_inner().0 = palette.clear;
+    area_name_text.into_inner().0 = palette.text;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_ecs_ldtk::ldtk::{FieldInstance, IntGridValueDefinition, LayerInstance, TileInstance};
+
+    fn level_named(name: Option<&str>) -> Level {
+        Level {
+            identifier: "Level_0".into(),
+            field_instances: vec![FieldInstance {
+                identifier: "Name".into(),
+                tile: None,
+                field_instance_type: "String".into(),
+                value: FieldValue::String(name.map(String::from)),
+                def_uid: 0,
+                real_editor_values: vec![],
+            }],
+            ..default()
+        }
+    }
+
+    /// Whether two colours are the same once stored with eight bits per channel.
+    fn same_color(a: Color, b: Color) -> bool {
+        let (a, b) = (a.to_srgba().to_f32_array(), b.to_srgba().to_f32_array());
+        a.iter().zip(b).all(|(a, b)| (a - b).abs() <= 1. / 255.)
+    }
+
+    #[test]
+    fn area_name_prefers_the_name_field() {
+        assert_eq!(
+            area_name(&level_named(Some("Mossy Clearing"))),
+            "Mossy Clearing"
+        );
+        assert_eq!(area_name(&level_named(None)), "Level_0");
+        assert_eq!(
+            area_name(&Level {
+                identifier: "Level_1".into(),
+                ..default()
+            }),
+            "Level_1"
+        );
+    }
+
+    #[test]
+    fn minimap_draws_int_grid_over_tiles() {
+        let water = Color::srgb(0., 0., 1.);
+        let definitions = [LayerDefinition {
+            uid: 1,
+            int_grid_values: vec![IntGridValueDefinition {
+                value: 2,
+                color: water,
+                ..default()
+            }],
+            ..default()
+        }];
+        let tile = |x, y| TileInstance {
+            px: IVec2::new(x, y) * MINIMAP_SCALE,
+            ..default()
+        };
+        let level = Level {
+            px_wid: 2 * MINIMAP_SCALE,
+            px_hei: 2 * MINIMAP_SCALE,
+            layer_instances: Some(vec![
+                LayerInstance {
+                    layer_instance_type: Type::IntGrid,
+                    layer_def_uid: 1,
+                    c_wid: 2,
+                    grid_size: MINIMAP_SCALE,
+                    int_grid_csv: vec![0, 2, 0, 0],
+                    ..default()
+                },
+                LayerInstance {
+                    layer_instance_type: Type::Tiles,
+                    // The tile off of the level is left off of the map.
+                    grid_tiles: vec![tile(1, 0), tile(0, 1), tile(5, 5)],
+                    ..default()
+                },
+            ]),
+            ..default()
+        };
+
+        let image = minimap_image(&level, &definitions);
+
+        assert_eq!(image.width(), 2);
+        assert_eq!(image.height(), 2);
+        assert_eq!(image.get_color_at(0, 0).unwrap().alpha(), 0.);
+        assert!(same_color(image.get_color_at(1, 0).unwrap(), water));
+        assert!(same_color(image.get_color_at(0, 1).unwrap(), FLOOR_COLOR));
+    }
+}
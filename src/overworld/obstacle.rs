@@ -0,0 +1,204 @@
+use crate::creature::roster::Roster;
+use crate::creature::FieldMove;
+use crate::overworld::player::Player;
+use crate::save::{SaveFile, SavePlugin};
+use crate::settings::binding_just_pressed;
+use crate::Despawn;
+use avian2d::prelude::{Collider, RigidBody};
+use bevy::prelude::*;
+use bevy_ecs_ldtk::ldtk::ldtk_fields::LdtkFieldsError;
+use bevy_ecs_ldtk::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// How close the player must be to an obstacle to interact with it.
+const INTERACT_DISTANCE: f32 = 24.;
+
+/// How long an obstacle takes to disappear once it has been cleared.
+const CLEAR_SECONDS: f32 = 0.5;
+
+pub struct ObstaclePlugin;
+impl Plugin for ObstaclePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_ldtk_entity::<ObstacleBundle>("CutTree")
+            .register_ldtk_entity::<ObstacleBundle>("Boulder")
+            .register_ldtk_entity::<ObstacleBundle>("WaterCurrent")
+            .register_type::<PlacedObstacle>()
+            .register_type::<Obstacle>()
+            .register_type::<Clearing>()
+            .register_type::<ClearedObstacles>()
+            .add_plugins(SavePlugin::<ClearedObstacles>::default())
+            .add_systems(Update, (setup_obstacles, animate_clearing))
+            .add_systems(
+                Update,
+                clear_obstacle.run_if(binding_just_pressed(|bindings| bindings.interact)),
+            );
+    }
+}
+
+/// Something in the overworld that blocks the player until it is cleared with a [`FieldMove`].
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct Obstacle {
+    requires: FieldMove,
+}
+
+/// An obstacle placed in LDtk whose [`FieldMove`] has not been read yet.
+#[derive(Component, Reflect, Debug, Default)]
+#[reflect(Component)]
+struct PlacedObstacle;
+
+#[derive(Bundle, Default, LdtkEntity)]
+struct ObstacleBundle {
+    placed: PlacedObstacle,
+
+    #[sprite_sheet]
+    sprite_sheet: Sprite,
+}
+
+fn required_field_move(instance: &EntityInstance) -> Result<FieldMove, String> {
+    let requires = match instance.get_enum_field("FieldMove") {
+        Ok(field_move) => field_move,
+        Err(LdtkFieldsError::UnexpectedNull { .. }) => return Err("its field move is null".into()),
+        Err(_) => return Err("its field move is missing or the wrong type".into()),
+    };
+
+    FieldMove::from_ldtk(requires).ok_or_else(|| format!("{requires} is not a field move"))
+}
+
+/// An obstacle that is playing its clear animation.
+#[derive(Component, Reflect, Debug, Deref, DerefMut)]
+#[reflect(Component)]
+struct Clearing(Timer);
+
+/// The obstacles that have been cleared, keyed by the iid of their level, so that they stay
+/// cleared between runs.
+#[derive(Resource, Reflect, Serialize, Deserialize, Debug, Default)]
+#[reflect(Resource)]
+pub struct ClearedObstacles(HashMap<String, HashSet<String>>);
+
+impl SaveFile for ClearedObstacles {
+    const FILE_NAME: &'static str = "cleared_obstacles.ron";
+    const DESCRIPTION: &'static str = "the cleared obstacles";
+}
+
+impl ClearedObstacles {
+    fn contains(&self, level: &LevelIid, obstacle: &EntityIid) -> bool {
+        self.0
+            .get(level.as_str())
+            .is_some_and(|cleared| cleared.contains(obstacle.as_str()))
+    }
+
+    fn insert(&mut self, level: &LevelIid, obstacle: &EntityIid) {
+        self.0
+            .entry(level.to_string())
+            .or_default()
+            .insert(obstacle.as_str().to_owned());
+    }
+}
+
+fn level_of<'a>(
+    entity: Entity,
+    parents: &Query<&ChildOf>,
+    levels: &'a Query<&LevelIid>,
+) -> Option<&'a LevelIid> {
+    parents
+        .iter_ancestors(entity)
+        .find_map(|ancestor| levels.get(ancestor).ok())
+}
+
+fn setup_obstacles(
+    mut commands: Commands,
+    cleared: Res<ClearedObstacles>,
+    obstacles: Query<(Entity, &EntityInstance, &EntityIid), Added<PlacedObstacle>>,
+    parents: Query<&ChildOf>,
+    levels: Query<&LevelIid>,
+) {
+    for (entity, instance, iid) in obstacles {
+        let already_cleared =
+            level_of(entity, &parents, &levels).is_some_and(|level| cleared.contains(level, iid));
+
+        if already_cleared {
+            commands.entity(entity).insert(Despawn);
+            continue;
+        }
+
+        let requires = match required_field_move(instance) {
+            Ok(requires) => requires,
+            Err(error) => {
+                warn!(
+                    "Skipping {} obstacle {}, as {error}.",
+                    instance.identifier,
+                    iid.as_str()
+                );
+                commands.entity(entity).insert(Despawn);
+                continue;
+            }
+        };
+
+        commands.entity(entity).insert((
+            Obstacle { requires },
+            RigidBody::Static,
+            Collider::rectangle(instance.width as f32, instance.height as f32),
+        ));
+    }
+}
+
+fn clear_obstacle(
+    mut commands: Commands,
+    mut cleared: ResMut<ClearedObstacles>,
+    roster: Res<Roster>,
+    player: Single<&GlobalTransform, With<Player>>,
+    obstacles: Query<(Entity, &Obstacle, &EntityIid, &GlobalTransform), Without<Clearing>>,
+    parents: Query<&ChildOf>,
+    levels: Query<&LevelIid>,
+) {
+    let player = player.translation().xy();
+    let nearest = obstacles
+        .iter()
+        .map(|obstacle| (obstacle, obstacle.3.translation().xy().distance(player)))
+        .filter(|(_, distance)| *distance <= INTERACT_DISTANCE)
+        .min_by(|(_, a), (_, b)| a.total_cmp(b));
+
+    let Some(((entity, obstacle, iid, _), _)) = nearest else {
+        return;
+    };
+
+    if !roster.has_field_move(obstacle.requires) {
+        info!(
+            "A creature that knows {:?} is needed here.",
+            obstacle.requires
+        );
+        return;
+    }
+
+    if let Some(level) = level_of(entity, &parents, &levels) {
+        cleared.insert(level, iid);
+    }
+
+    commands
+        .entity(entity)
+        .remove::<(RigidBody, Collider)>()
+        .insert(Clearing(Timer::from_seconds(
+            CLEAR_SECONDS,
+            TimerMode::Once,
+        )));
+}
+
+fn animate_clearing(
+    mut commands: Commands,
+    time: Res<Time>,
+    obstacles: Query<(Entity, &mut Clearing, &mut Sprite, &mut Transform), Without<Despawn>>,
+) {
+    for (entity, mut clearing, mut sprite, mut transform) in obstacles {
+        clearing.tick(time.delta());
+
+        let remaining = 1. - clearing.fraction();
+        sprite.color.set_alpha(remaining);
+        transform.scale = Vec3::splat(remaining);
+
+        if clearing.finished() {
+            commands.entity(entity).insert(Despawn);
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use crate::creature::roster::Roster;
+use crate::movement::{MovementVector, Speed};
+use crate::overworld::player::{Player, Trail};
+use crate::Despawn;
+use bevy::prelude::*;
+
+/// How far the player must move before another point is added to their trail.
+const TRAIL_SPACING: f32 = 4.;
+
+/// How many points the trail holds, which decides how far behind the partner walks.
+const TRAIL_LENGTH: usize = 8;
+
+pub struct PartnerPlugin;
+impl Plugin for PartnerPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Partner>()
+            .register_type::<Trail>()
+            .add_systems(Update, sync_partner)
+            .add_systems(FixedUpdate, (record_trail, follow_trail).chain());
+    }
+}
+
+/// The overworld representation of the active partner in the [`Roster`].
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+#[require(Speed)]
+struct Partner(usize);
+
+fn sync_partner(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    roster: Res<Roster>,
+    partner: Option<Single<(Entity, &Partner), Without<Despawn>>>,
+    player: Option<Single<&GlobalTransform, With<Player>>>,
+) {
+    let current = partner.as_ref().map(|partner| partner.1 .0);
+    if current == roster.partner {
+        return;
+    }
+
+    if let Some(partner) = partner {
+        commands.entity(partner.0).insert(Despawn);
+    }
+
+    let (Some(index), Some(entry), Some(player)) = (roster.partner, roster.partner(), player)
+    else {
+        return;
+    };
+
+    let mut sprite = Sprite::from_image(asset_server.load("TempEnemy.png"));
+    sprite.custom_size = Some(Vec2::new(16., 16.));
+    commands.spawn((
+        Name::from(entry.nickname.clone()),
+        Partner(index),
+        sprite,
+        Transform::from_translation(player.translation()),
+    ));
+}
+
+fn record_trail(player: Single<(&GlobalTransform, &mut Trail), With<Player>>) {
+    let (transform, mut trail) = player.into_inner();
+    let position = transform.translation().xy();
+
+    match trail.back() {
+        Some(last) if last.distance(position) < TRAIL_SPACING => return,
+        _ => trail.push_back(position),
+    }
+
+    while trail.len() > TRAIL_LENGTH {
+        trail.pop_front();
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn follow_trail(
+    player: Single<(&Trail, &Speed), With<Player>>,
+    partner: Single<
+        (&GlobalTransform, &mut MovementVector, &mut Speed),
+        (With<Partner>, Without<Player>),
+    >,
+) {
+    let (trail, player_speed) = player.into_inner();
+    let (transform, mut movement, mut speed) = partner.into_inner();
+
+    speed.0 = player_speed.0;
+    movement.0 = match trail.front() {
+        Some(target) if target.distance(transform.translation().xy()) > TRAIL_SPACING / 2. => {
+            target - transform.translation().xy()
+        }
+        _ => Vec2::ZERO,
+    };
+}
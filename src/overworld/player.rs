@@ -1,14 +1,23 @@
 use crate::camera::GizmoCamera;
+use crate::capture::config::CaptureConfig;
+use crate::capture::styler::Styler;
 use crate::movement::{MovementVector, Speed};
-use bevy::input::common_conditions::input_pressed;
+use crate::settings::mouse_binding_pressed;
 use bevy::prelude::*;
 use bevy::window::PrimaryWindow;
 use bevy_ecs_ldtk::ldtk::ldtk_fields::LdtkFieldsError;
 use bevy_ecs_ldtk::prelude::*;
+use std::collections::VecDeque;
 
 #[derive(Component, Reflect, Debug, Default)]
 #[reflect(Component)]
-struct Player;
+#[require(Trail)]
+pub(super) struct Player;
+
+/// The most recent positions of the player, oldest first.
+#[derive(Component, Reflect, Debug, Default, Deref, DerefMut)]
+#[reflect(Component)]
+pub(super) struct Trail(VecDeque<Vec2>);
 
 #[derive(Bundle, Default, LdtkEntity)]
 struct PlayerBundle {
@@ -61,6 +70,13 @@ fn to_cursor_pos(
     }
 }
 
+/// Gives the player a styler to capture with, which upgrades once the player has it.
+fn give_styler(added: Trigger<OnAdd, Player>, mut commands: Commands, config: Res<CaptureConfig>) {
+    commands
+        .entity(added.target())
+        .insert(Styler::base(&config));
+}
+
 fn clear_movement(player: Single<&mut MovementVector, With<Player>>) {
     player.into_inner().0 = Vec2::ZERO;
 }
@@ -69,10 +85,14 @@ pub struct PlayerPlugin;
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
         app.register_ldtk_entity::<PlayerBundle>("Player")
+            .add_observer(give_styler)
             .add_systems(
                 First,
-                clear_movement.run_if(not(input_pressed(MouseButton::Left))),
+                clear_movement.run_if(not(mouse_binding_pressed(|bindings| bindings.walk))),
             )
-            .add_systems(Last, to_cursor_pos.run_if(input_pressed(MouseButton::Left)));
+            .add_systems(
+                Last,
+                to_cursor_pos.run_if(mouse_binding_pressed(|bindings| bindings.walk)),
+            );
     }
 }
@@ -0,0 +1,94 @@
+use crate::overworld::player::Player;
+use bevy::prelude::*;
+use bevy_ecs_ldtk::ldtk::Level;
+use bevy_ecs_ldtk::prelude::*;
+use std::collections::HashMap;
+
+/// Keeps the overworld playable when `overworld.ldtk` is reloaded from disk.
+///
+/// `bevy_ecs_ldtk` respawns the whole world when its project changes, so this puts the player
+/// back where they were and logs which levels changed.
+pub struct ReloadPlugin;
+impl Plugin for ReloadPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LoadedLevels>()
+            .add_observer(preserve_player)
+            .add_systems(
+                Update,
+                log_level_changes.run_if(on_event::<AssetEvent<LdtkProject>>),
+            )
+            .add_systems(Update, restore_player);
+    }
+}
+
+/// The levels as they were last loaded, by iid.
+#[derive(Resource, Debug, Default)]
+struct LoadedLevels(HashMap<String, Level>);
+
+/// Where the player was before the world was respawned.
+#[derive(Resource, Debug)]
+struct PreservedPlayer(Transform);
+
+fn log_level_changes(
+    mut events: EventReader<AssetEvent<LdtkProject>>,
+    mut loaded: ResMut<LoadedLevels>,
+    projects: Res<Assets<LdtkProject>>,
+) {
+    for event in events.read() {
+        let (AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id }) = event
+        else {
+            continue;
+        };
+        let Some(project) = projects.get(*id) else {
+            continue;
+        };
+
+        let levels = project
+            .iter_raw_levels()
+            .map(|level| (level.iid.clone(), level.clone()))
+            .collect::<HashMap<_, _>>();
+
+        if matches!(event, AssetEvent::Modified { .. }) {
+            for (iid, level) in &levels {
+                match loaded.0.get(iid) {
+                    None => info!("Level {} added", level.identifier),
+                    Some(old) if old != level => info!("Level {} changed", level.identifier),
+                    _ => {}
+                }
+            }
+            for (iid, level) in &loaded.0 {
+                if !levels.contains_key(iid) {
+                    info!("Level {} removed", level.identifier);
+                }
+            }
+        }
+
+        loaded.0 = levels;
+    }
+}
+
+/// The player is only ever despawned when the world is respawned, so remember where they were.
+fn preserve_player(
+    trigger: Trigger<OnRemove, Player>,
+    mut commands: Commands,
+    players: Query<&Transform>,
+) {
+    if let Ok(transform) = players.get(trigger.target()) {
+        commands.insert_resource(PreservedPlayer(*transform));
+    }
+}
+
+fn restore_player(
+    mut commands: Commands,
+    preserved: Option<Res<PreservedPlayer>>,
+    player: Option<Single<&mut Transform, Added<Player>>>,
+) {
+    let (Some(preserved), Some(player)) = (preserved, player) else {
+        return;
+    };
+
+    let mut transform = player.into_inner();
+    transform.translation = preserved.0.translation.with_z(transform.translation.z);
+    info!("Restored player position after reload");
+    commands.remove_resource::<PreservedPlayer>();
+}
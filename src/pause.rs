@@ -0,0 +1,193 @@
+use crate::capture::CaptureLine;
+use crate::settings::{rebinding_key, CursorLeavePolicy, Settings};
+use crate::ui::{BUTTON_COLOR, MENU_BACKGROUND_COLOR};
+use bevy::prelude::*;
+use bevy::window::{CursorLeft, WindowFocused};
+
+/// Stops virtual time while the pause menu is open, and slows it down for [`SlowMotion`].
+///
+/// Everything that should freeze reads [`Time`] in `Update` or runs in `FixedUpdate`, so pausing
+/// [`Time<Virtual>`] is all it takes.
+pub struct PausePlugin;
+impl Plugin for PausePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<PauseMenu>()
+            .register_type::<SlowMotion>()
+            .add_systems(Startup, spawn_pause_menu)
+            .add_systems(
+                Update,
+                (
+                    toggle_pause.run_if(pause_just_pressed.and(not(rebinding_key))),
+                    pause_on_unfocus.run_if(not(paused)),
+                    resume_from_button.run_if(paused),
+                    update_slow_motion,
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// Represents when the game has been paused.
+#[derive(Event, Debug)]
+pub struct GamePaused;
+
+/// Represents when the game has been resumed after being paused.
+#[derive(Event, Debug)]
+pub struct GameResumed;
+
+/// Scales virtual time by `scale` until `timer` finishes, which counts real time.
+#[derive(Resource, Reflect, Debug)]
+#[reflect(Resource)]
+pub struct SlowMotion {
+    pub scale: f32,
+    pub timer: Timer,
+}
+
+impl SlowMotion {
+    pub fn new(scale: f32, seconds: f32) -> Self {
+        Self {
+            scale,
+            timer: Timer::from_seconds(seconds, TimerMode::Once),
+        }
+    }
+}
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct PauseMenu;
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct ResumeButton;
+
+/// Run condition for while the game is paused.
+pub(crate) fn paused(time: Res<Time<Virtual>>) -> bool {
+    time.is_paused()
+}
+
+fn pause_just_pressed(
+    settings: Res<Settings>,
+    keys: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+) -> bool {
+    keys.just_pressed(settings.bindings.pause)
+        || gamepads
+            .iter()
+            .any(|gamepad| gamepad.just_pressed(GamepadButton::Start))
+}
+
+fn spawn_pause_menu(mut commands: Commands) {
+    commands.spawn((
+        Name::from("Pause Menu"),
+        PauseMenu,
+        Node {
+            position_type: PositionType::Absolute,
+            align_self: AlignSelf::Center,
+            justify_self: JustifySelf::Center,
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            padding: UiRect::all(Val::Px(12.)),
+            row_gap: Val::Px(6.),
+            ..default()
+        },
+        BackgroundColor(MENU_BACKGROUND_COLOR),
+        Visibility::Hidden,
+        children![
+            Text::new("Paused"),
+            (
+                Button,
+                ResumeButton,
+                Node {
+                    padding: UiRect::axes(Val::Px(6.), Val::Px(2.)),
+                    ..default()
+                },
+                BackgroundColor(BUTTON_COLOR),
+                children![Text::new("Resume")],
+            ),
+        ],
+    ));
+}
+
+fn set_paused(
+    paused: bool,
+    commands: &mut Commands,
+    time: &mut Time<Virtual>,
+    menu: &mut Visibility,
+) {
+    if paused {
+        time.pause();
+        *menu = Visibility::Inherited;
+        commands.trigger(GamePaused);
+    } else {
+        time.unpause();
+        *menu = Visibility::Hidden;
+        commands.trigger(GameResumed);
+    }
+}
+
+fn toggle_pause(
+    mut commands: Commands,
+    mut time: ResMut<Time<Virtual>>,
+    menu: Single<&mut Visibility, With<PauseMenu>>,
+) {
+    let paused = !time.is_paused();
+    set_paused(paused, &mut commands, &mut time, &mut menu.into_inner());
+}
+
+/// Pauses instead of ending the capture when the window loses focus, or when the cursor leaves it
+/// mid-capture with [`CursorLeavePolicy::Pause`], so the line is still there when the player
+/// comes back.
+fn pause_on_unfocus(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    mut focus: EventReader<WindowFocused>,
+    mut cursor_left: EventReader<CursorLeft>,
+    mut time: ResMut<Time<Virtual>>,
+    capture_line: Query<(), With<CaptureLine>>,
+    menu: Single<&mut Visibility, With<PauseMenu>>,
+) {
+    let unfocused = focus.read().any(|event| !event.focused);
+    let left_mid_capture = cursor_left.read().count() > 0
+        && !capture_line.is_empty()
+        && settings.cursor_leave == CursorLeavePolicy::Pause;
+    if unfocused || left_mid_capture {
+        set_paused(true, &mut commands, &mut time, &mut menu.into_inner());
+    }
+}
+
+fn resume_from_button(
+    mut commands: Commands,
+    mut time: ResMut<Time<Virtual>>,
+    buttons: Query<&Interaction, (Changed<Interaction>, With<ResumeButton>)>,
+    menu: Single<&mut Visibility, With<PauseMenu>>,
+) {
+    if buttons
+        .iter()
+        .any(|interaction| *interaction == Interaction::Pressed)
+    {
+        set_paused(false, &mut commands, &mut time, &mut menu.into_inner());
+    }
+}
+
+fn update_slow_motion(
+    mut commands: Commands,
+    real_time: Res<Time<Real>>,
+    mut time: ResMut<Time<Virtual>>,
+    slow_motion: Option<ResMut<SlowMotion>>,
+) {
+    let Some(mut slow_motion) = slow_motion else {
+        return;
+    };
+
+    // Slow motion shouldn't run out while the game is paused.
+    if !time.is_paused() {
+        slow_motion.timer.tick(real_time.delta());
+    }
+
+    if slow_motion.timer.finished() {
+        time.set_relative_speed(1.);
+        commands.remove_resource::<SlowMotion>();
+    } else {
+        time.set_relative_speed(slow_motion.scale);
+    }
+}
@@ -0,0 +1,193 @@
+//! Everything the game keeps between runs.
+//!
+//! Each [`SaveFile`] is a resource saved as RON in the player's config directory. It is loaded
+//! when its [`SavePlugin`] is added, and written on the IO task pool once it has gone
+//! [`SAVE_DELAY`] without changing, or straight away when the app exits.
+
+use bevy::prelude::*;
+use bevy::tasks::{block_on, IoTaskPool, Task};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs;
+use std::io;
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// How long a save file has to stay the same before it is written, so that dragging a slider
+/// writes the file once instead of every frame.
+const SAVE_DELAY: Duration = Duration::from_millis(500);
+
+/// Loads and saves `T`, leaving it alone if it was inserted before the plugin was added.
+pub(crate) struct SavePlugin<T>(PhantomData<T>);
+impl<T: SaveFile> Plugin for SavePlugin<T> {
+    fn build(&self, app: &mut App) {
+        if !app.world().contains_resource::<T>() {
+            app.insert_resource(T::load());
+        }
+
+        app.init_resource::<PendingSave<T>>()
+            .add_systems(Update, save_when_settled::<T>)
+            .add_systems(Last, save_on_exit::<T>.run_if(on_event::<AppExit>));
+    }
+}
+
+impl<T> Default for SavePlugin<T> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+/// A resource that is kept between runs in its own file.
+pub trait SaveFile: Resource + Serialize + DeserializeOwned + Default {
+    const FILE_NAME: &'static str;
+
+    /// What the file holds, for log messages.
+    const DESCRIPTION: &'static str;
+
+    fn path() -> PathBuf {
+        save_path(Self::FILE_NAME)
+    }
+
+    /// Reads the file from [`SaveFile::path`], or the default if it hasn't been saved yet.
+    fn read() -> io::Result<Self> {
+        Self::read_from(&Self::path())
+    }
+
+    /// Like [`SaveFile::read`], starting over from the default if the file can't be read.
+    fn load() -> Self {
+        Self::read().unwrap_or_else(|e| {
+            warn!(
+                "Unable to read {} from {}: {e}",
+                Self::DESCRIPTION,
+                Self::path().display()
+            );
+            Self::default()
+        })
+    }
+
+    fn save(&self) -> io::Result<()> {
+        self.write_to(&Self::path())
+    }
+
+    fn read_from(path: &Path) -> io::Result<Self> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(e),
+        };
+
+        ron::from_str(&contents).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    fn write_to(&self, path: &Path) -> io::Result<()> {
+        write(path, &self.to_ron()?)
+    }
+
+    fn to_ron(&self) -> io::Result<String> {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(io::Error::other)
+    }
+}
+
+/// Where a file the game saves is kept, in the user's config directory if one can be found.
+pub(crate) fn save_path(file_name: &str) -> PathBuf {
+    let config_dir = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("APPDATA").map(PathBuf::from))
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")));
+
+    match config_dir {
+        Some(dir) => dir.join("bevymon_ranger").join(file_name),
+        None => PathBuf::from(file_name),
+    }
+}
+
+/// Writes to a file beside `path` and moves it into place, so that a write that is cut short
+/// leaves the last save as it was.
+pub(crate) fn write(path: &Path, contents: impl AsRef<[u8]>) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    let partial = path.with_extension("partial");
+    fs::write(&partial, contents)?;
+    fs::rename(partial, path)
+}
+
+/// A change to `T` that hasn't been written yet, and the write in progress.
+#[derive(Resource)]
+struct PendingSave<T> {
+    /// Counts down from the latest change that hasn't been written.
+    delay: Option<Timer>,
+
+    /// Finishes before the next write starts, so that writes land in order.
+    writing: Option<Task<io::Result<()>>>,
+    marker: PhantomData<T>,
+}
+
+impl<T> Default for PendingSave<T> {
+    fn default() -> Self {
+        Self {
+            delay: None,
+            writing: None,
+            marker: PhantomData,
+        }
+    }
+}
+
+fn save_when_settled<T: SaveFile>(
+    resource: Res<T>,
+    mut pending: ResMut<PendingSave<T>>,
+    time: Res<Time<Real>>,
+) {
+    if resource.is_changed() && !resource.is_added() {
+        pending.delay = Some(Timer::new(SAVE_DELAY, TimerMode::Once));
+    }
+
+    if pending
+        .writing
+        .as_ref()
+        .is_some_and(|task| !task.is_finished())
+    {
+        return;
+    }
+
+    if let Some(task) = pending.writing.take() {
+        log_save_error::<T>(block_on(task));
+    }
+
+    let Some(delay) = &mut pending.delay else {
+        return;
+    };
+    if !delay.tick(time.delta()).finished() {
+        return;
+    }
+
+    pending.delay = None;
+    match resource.to_ron() {
+        Ok(contents) => {
+            let path = T::path();
+            let task = IoTaskPool::get().spawn(async move { write(&path, &contents) });
+            pending.writing = Some(task);
+        }
+        Err(e) => log_save_error::<T>(Err(e)),
+    }
+}
+
+/// Waits for the write in progress, and writes any change that is still waiting.
+fn save_on_exit<T: SaveFile>(resource: Res<T>, mut pending: ResMut<PendingSave<T>>) {
+    if let Some(task) = pending.writing.take() {
+        log_save_error::<T>(block_on(task));
+    }
+
+    if pending.delay.take().is_some() {
+        log_save_error::<T>(resource.save());
+    }
+}
+
+fn log_save_error<T: SaveFile>(result: io::Result<()>) {
+    if let Err(e) = result {
+        error!("Unable to save {}: {e}", T::DESCRIPTION);
+    }
+}
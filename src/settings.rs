@@ -0,0 +1,332 @@
+mod menu;
+
+use crate::camera::{CLEAR_COLOR, RESOLUTION};
+use crate::save::{SaveFile, SavePlugin};
+use crate::settings::menu::SettingsMenuPlugin;
+
+pub(crate) use crate::settings::menu::rebinding_key;
+use bevy::audio::Volume;
+use bevy::color::palettes::css;
+use bevy::log::{Level, LogPlugin};
+use bevy::prelude::*;
+use bevy::window::{MonitorSelection, PrimaryWindow, VideoModeSelection, WindowMode};
+use serde::{Deserialize, Serialize};
+
+/// Loads, applies and saves the player's [`Settings`].
+pub struct SettingsPlugin;
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Settings>()
+            .add_plugins(SavePlugin::<Settings>::default())
+            .add_plugins(SettingsMenuPlugin)
+            .add_systems(
+                Update,
+                apply_settings
+                    .run_if(resource_changed::<Settings>.and(not(resource_added::<Settings>))),
+            )
+            .add_systems(Startup, apply_settings);
+    }
+}
+
+/// Everything the player can configure, saved to a config file whenever it changes.
+#[derive(Resource, Reflect, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[reflect(Resource)]
+#[serde(default)]
+pub struct Settings {
+    pub window_mode: WindowModeSetting,
+
+    /// How many times larger than the game's resolution the window is.
+    pub scale: u32,
+
+    /// From `0.` for silent to `1.` for full volume.
+    pub volume: f32,
+    pub line_colors: LineColorScheme,
+
+    /// Replaces the game's colours with ones that are easier to tell apart.
+    pub palette: Palette,
+    pub bindings: Bindings,
+
+    /// What happens to a capture when the cursor leaves the window.
+    pub cursor_leave: CursorLeavePolicy,
+
+    /// How long [`CursorLeavePolicy::Wait`] waits for the cursor to come back, in milliseconds.
+    pub cursor_grace_ms: u64,
+
+    /// Turns off effects that move or flash, such as sparkles and wobbling.
+    pub reduced_motion: bool,
+    pub log_filter: String,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            window_mode: WindowModeSetting::Windowed,
+            scale: 2,
+            volume: 1.,
+            line_colors: LineColorScheme::Sky,
+            palette: Palette::Standard,
+            bindings: Bindings::default(),
+            cursor_leave: CursorLeavePolicy::Wait,
+            cursor_grace_ms: 500,
+            reduced_motion: false,
+            log_filter: "info,wgpu_core=off,wgpu_hal=off,mygame=debug".into(),
+        }
+    }
+}
+
+impl SaveFile for Settings {
+    const FILE_NAME: &'static str = "settings.ron";
+    const DESCRIPTION: &'static str = "settings";
+}
+
+impl Settings {
+    pub const MAX_SCALE: u32 = 6;
+
+    /// The colours the capture line fades between, which the palette takes over from the
+    /// chosen [`LineColorScheme`] unless it is [`Palette::Standard`].
+    pub fn line_gradient(&self) -> (Color, Color) {
+        self.palette
+            .colors()
+            .line
+            .unwrap_or_else(|| self.line_colors.colors())
+    }
+
+    /// The primary window, as these settings describe it.
+    pub fn window(&self) -> Window {
+        let size = RESOLUTION * self.scale as f32;
+        Window {
+            title: "bevymon_ranger".into(),
+            name: Some("bevymon_ranger".into()),
+            mode: self.window_mode.into(),
+            resolution: size.into(),
+
+            // This allows i3wm to force it into floating.
+            resizable: false,
+            ..default()
+        }
+    }
+
+    /// Logging as [`Settings::log_filter`] describes it, at the level of its bare directive, such
+    /// as the `info` in `info,wgpu_core=off`.
+    pub fn log_plugin(&self) -> LogPlugin {
+        let level = self
+            .log_filter
+            .split(',')
+            .find_map(|directive| directive.trim().parse().ok())
+            .unwrap_or(Level::INFO);
+
+        LogPlugin {
+            filter: self.log_filter.clone(),
+            level,
+            ..default()
+        }
+    }
+}
+
+#[derive(Reflect, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WindowModeSetting {
+    Windowed,
+    BorderlessFullscreen,
+    Fullscreen,
+}
+
+impl From<WindowModeSetting> for WindowMode {
+    fn from(mode: WindowModeSetting) -> Self {
+        match mode {
+            WindowModeSetting::Windowed => WindowMode::Windowed,
+            WindowModeSetting::BorderlessFullscreen => {
+                WindowMode::BorderlessFullscreen(MonitorSelection::Current)
+            }
+            WindowModeSetting::Fullscreen => {
+                WindowMode::Fullscreen(MonitorSelection::Current, VideoModeSelection::Current)
+            }
+        }
+    }
+}
+
+/// The colours the capture line fades between, from its oldest point to its newest.
+#[derive(Reflect, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineColorScheme {
+    Sky,
+    Ember,
+    Forest,
+    Mono,
+}
+
+impl LineColorScheme {
+    pub fn colors(&self) -> (Color, Color) {
+        match self {
+            LineColorScheme::Sky => (
+                Color::linear_rgb(0.168_627_46, 0.211_764_71, 0.529_411_8),
+                Color::linear_rgb(0.411_764_7, 0.478_431_37, 0.980_392_16),
+            ),
+            LineColorScheme::Ember => (
+                Color::linear_rgb(0.529_411_8, 0.168_627_46, 0.082_352_94),
+                Color::linear_rgb(0.980_392_16, 0.647_058_84, 0.211_764_71),
+            ),
+            LineColorScheme::Forest => (
+                Color::linear_rgb(0.082_352_94, 0.349_019_62, 0.168_627_46),
+                Color::linear_rgb(0.552_941_2, 0.909_803_9, 0.439_215_7),
+            ),
+            LineColorScheme::Mono => (Color::linear_rgb(0.4, 0.4, 0.4), Color::WHITE),
+        }
+    }
+}
+
+/// What happens to a capture when the cursor leaves the window.
+#[derive(Reflect, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CursorLeavePolicy {
+    /// Keeps drawing with the styler held at the edge of the viewport.
+    Clamp,
+
+    /// Stops drawing until the cursor comes back, cancelling the capture if it takes longer than
+    /// [`Settings::cursor_grace_ms`].
+    Wait,
+
+    /// Pauses the game.
+    Pause,
+
+    /// Cancels the capture straight away.
+    Cancel,
+}
+
+/// A set of colours for everything that needs to be told apart at a glance.
+#[derive(Reflect, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Palette {
+    Standard,
+    Deuteranopia,
+    Protanopia,
+    Tritanopia,
+    HighContrast,
+}
+
+/// The colours a [`Palette`] uses.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PaletteColors {
+    /// Overrides the [`LineColorScheme`] if set.
+    pub line: Option<(Color, Color)>,
+
+    /// Capture counts once a creature has been looped enough.
+    pub success: Color,
+
+    /// Capture counts that are about to decay.
+    pub warning: Color,
+    pub text: Color,
+
+    /// Behind HUD text such as the area name.
+    pub panel: Color,
+    pub clear: Color,
+}
+
+impl Palette {
+    pub fn colors(&self) -> PaletteColors {
+        let standard = PaletteColors {
+            line: None,
+            success: css::LIGHT_SEA_GREEN.into(),
+            warning: css::ORANGE.into(),
+            text: Color::WHITE,
+            panel: Color::linear_rgba(0.066, 0.060, 0.060, 0.624),
+            clear: CLEAR_COLOR,
+        };
+
+        // Based on the Okabe-Ito palette, which avoids the pairs each kind of colour blindness
+        // confuses.
+        match self {
+            Palette::Standard => standard,
+            Palette::Deuteranopia => PaletteColors {
+                line: Some((Color::srgb_u8(0, 114, 178), Color::srgb_u8(86, 180, 233))),
+                success: Color::srgb_u8(86, 180, 233),
+                warning: Color::srgb_u8(230, 159, 0),
+                ..standard
+            },
+            Palette::Protanopia => PaletteColors {
+                line: Some((Color::srgb_u8(0, 114, 178), Color::srgb_u8(86, 180, 233))),
+                success: Color::srgb_u8(86, 180, 233),
+                warning: Color::srgb_u8(240, 228, 66),
+                ..standard
+            },
+            Palette::Tritanopia => PaletteColors {
+                line: Some((Color::srgb_u8(136, 34, 85), Color::srgb_u8(204, 121, 167))),
+                success: Color::srgb_u8(0, 158, 115),
+                warning: Color::srgb_u8(213, 94, 0),
+                ..standard
+            },
+            Palette::HighContrast => PaletteColors {
+                line: Some((Color::srgb(1., 1., 0.), Color::WHITE)),
+                success: Color::srgb(0., 1., 1.),
+                warning: Color::srgb(1., 1., 0.),
+                text: Color::WHITE,
+                panel: Color::BLACK,
+                clear: Color::srgb(0.05, 0.05, 0.05),
+            },
+        }
+    }
+}
+
+/// Which buttons perform each action.
+#[derive(Reflect, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct Bindings {
+    /// Any of these starts drawing a capture line.
+    pub capture: Vec<MouseButton>,
+
+    /// Held to walk towards the cursor.
+    pub walk: MouseButton,
+    pub interact: KeyCode,
+    pub assist: KeyCode,
+    pub confirm: KeyCode,
+    pub settings_menu: KeyCode,
+    pub pause: KeyCode,
+    pub field_guide: KeyCode,
+    pub styler_menu: KeyCode,
+
+    /// Makes the next captured creature the partner.
+    pub partner: KeyCode,
+}
+
+impl Default for Bindings {
+    fn default() -> Self {
+        Self {
+            capture: vec![MouseButton::Left, MouseButton::Right],
+            walk: MouseButton::Left,
+            interact: KeyCode::KeyE,
+            assist: KeyCode::Space,
+            confirm: KeyCode::Enter,
+            settings_menu: KeyCode::F10,
+            pause: KeyCode::Escape,
+            field_guide: KeyCode::KeyG,
+            styler_menu: KeyCode::KeyU,
+            partner: KeyCode::Tab,
+        }
+    }
+}
+
+/// Run condition for when the key bound to an action was just pressed.
+pub(crate) fn binding_just_pressed(
+    binding: fn(&Bindings) -> KeyCode,
+) -> impl FnMut(Res<Settings>, Res<ButtonInput<KeyCode>>) -> bool + Clone {
+    move |settings, input| input.just_pressed(binding(&settings.bindings))
+}
+
+/// Run condition for while the mouse button bound to an action is held.
+#[cfg(feature = "overworld")]
+pub(crate) fn mouse_binding_pressed(
+    binding: fn(&Bindings) -> MouseButton,
+) -> impl FnMut(Res<Settings>, Res<ButtonInput<MouseButton>>) -> bool + Clone {
+    move |settings, input| input.pressed(binding(&settings.bindings))
+}
+
+fn apply_settings(
+    settings: Res<Settings>,
+    mut global_volume: ResMut<GlobalVolume>,
+    window: Option<Single<&mut Window, With<PrimaryWindow>>>,
+) {
+    global_volume.volume = Volume::Linear(settings.volume);
+
+    if let Some(window) = window {
+        let mut window = window.into_inner();
+        let size = RESOLUTION * settings.scale as f32;
+        window.mode = settings.window_mode.into();
+        window.resolution.set(size.x, size.y);
+    }
+}
@@ -0,0 +1,364 @@
+use crate::settings::{
+    binding_just_pressed, Bindings, CursorLeavePolicy, LineColorScheme, Palette, Settings,
+    WindowModeSetting,
+};
+use crate::ui::{BUTTON_COLOR, FONT_SIZE, MENU_BACKGROUND_COLOR};
+use bevy::prelude::*;
+
+const WINDOW_MODES: [WindowModeSetting; 3] = [
+    WindowModeSetting::Windowed,
+    WindowModeSetting::BorderlessFullscreen,
+    WindowModeSetting::Fullscreen,
+];
+
+const LINE_COLOR_SCHEMES: [LineColorScheme; 4] = [
+    LineColorScheme::Sky,
+    LineColorScheme::Ember,
+    LineColorScheme::Forest,
+    LineColorScheme::Mono,
+];
+
+const PALETTES: [Palette; 5] = [
+    Palette::Standard,
+    Palette::Deuteranopia,
+    Palette::Protanopia,
+    Palette::Tritanopia,
+    Palette::HighContrast,
+];
+
+const CURSOR_LEAVE_POLICIES: [CursorLeavePolicy; 4] = [
+    CursorLeavePolicy::Clamp,
+    CursorLeavePolicy::Wait,
+    CursorLeavePolicy::Pause,
+    CursorLeavePolicy::Cancel,
+];
+
+/// How much the cursor grace period changes with each press, in milliseconds.
+const GRACE_STEP_MS: u64 = 100;
+
+const MAX_GRACE_MS: u64 = 3000;
+
+/// How much the volume changes with each press.
+const VOLUME_STEP: f32 = 0.1;
+
+pub(super) struct SettingsMenuPlugin;
+impl Plugin for SettingsMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<SettingsMenu>()
+            .register_type::<SettingValue>()
+            .register_type::<AdjustSetting>()
+            .init_resource::<Rebinding>()
+            .add_systems(Startup, spawn_settings_menu)
+            .add_systems(
+                Update,
+                (
+                    toggle_settings_menu.run_if(
+                        binding_just_pressed(|bindings| bindings.settings_menu)
+                            .and(not(rebinding_key)),
+                    ),
+                    press_setting_buttons,
+                    rebind_key.run_if(rebinding_key),
+                    update_setting_values
+                        .run_if(resource_changed::<Settings>.or(resource_changed::<Rebinding>)),
+                )
+                    .chain(),
+            );
+    }
+}
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct SettingsMenu;
+
+/// An action that can be rebound from the menu.
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+enum KeyBinding {
+    Interact,
+    Assist,
+    Confirm,
+    SettingsMenu,
+    Pause,
+    FieldGuide,
+    StylerMenu,
+    Partner,
+}
+
+impl KeyBinding {
+    fn key(&self, bindings: &Bindings) -> KeyCode {
+        match self {
+            KeyBinding::Interact => bindings.interact,
+            KeyBinding::Assist => bindings.assist,
+            KeyBinding::Confirm => bindings.confirm,
+            KeyBinding::SettingsMenu => bindings.settings_menu,
+            KeyBinding::Pause => bindings.pause,
+            KeyBinding::FieldGuide => bindings.field_guide,
+            KeyBinding::StylerMenu => bindings.styler_menu,
+            KeyBinding::Partner => bindings.partner,
+        }
+    }
+
+    fn key_mut<'a>(&self, bindings: &'a mut Bindings) -> &'a mut KeyCode {
+        match self {
+            KeyBinding::Interact => &mut bindings.interact,
+            KeyBinding::Assist => &mut bindings.assist,
+            KeyBinding::Confirm => &mut bindings.confirm,
+            KeyBinding::SettingsMenu => &mut bindings.settings_menu,
+            KeyBinding::Pause => &mut bindings.pause,
+            KeyBinding::FieldGuide => &mut bindings.field_guide,
+            KeyBinding::StylerMenu => &mut bindings.styler_menu,
+            KeyBinding::Partner => &mut bindings.partner,
+        }
+    }
+}
+
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+enum SettingKind {
+    WindowMode,
+    Scale,
+    Volume,
+    LineColors,
+    Palette,
+    CursorLeave,
+    CursorGrace,
+    ReducedMotion,
+    Binding(KeyBinding),
+}
+
+impl SettingKind {
+    const ALL: [SettingKind; 16] = [
+        SettingKind::WindowMode,
+        SettingKind::Scale,
+        SettingKind::Volume,
+        SettingKind::LineColors,
+        SettingKind::Palette,
+        SettingKind::CursorLeave,
+        SettingKind::CursorGrace,
+        SettingKind::ReducedMotion,
+        SettingKind::Binding(KeyBinding::Interact),
+        SettingKind::Binding(KeyBinding::Assist),
+        SettingKind::Binding(KeyBinding::Confirm),
+        SettingKind::Binding(KeyBinding::SettingsMenu),
+        SettingKind::Binding(KeyBinding::Pause),
+        SettingKind::Binding(KeyBinding::FieldGuide),
+        SettingKind::Binding(KeyBinding::StylerMenu),
+        SettingKind::Binding(KeyBinding::Partner),
+    ];
+
+    fn label(&self) -> &'static str {
+        match self {
+            SettingKind::WindowMode => "Window",
+            SettingKind::Scale => "Scale",
+            SettingKind::Volume => "Volume",
+            SettingKind::LineColors => "Line colours",
+            SettingKind::Palette => "Palette",
+            SettingKind::CursorLeave => "Cursor leaves window",
+            SettingKind::CursorGrace => "Cursor grace period",
+            SettingKind::ReducedMotion => "Reduced motion",
+            SettingKind::Binding(KeyBinding::Interact) => "Interact",
+            SettingKind::Binding(KeyBinding::Assist) => "Assist",
+            SettingKind::Binding(KeyBinding::Confirm) => "Confirm",
+            SettingKind::Binding(KeyBinding::SettingsMenu) => "Settings menu",
+            SettingKind::Binding(KeyBinding::Pause) => "Pause",
+            SettingKind::Binding(KeyBinding::FieldGuide) => "Field guide",
+            SettingKind::Binding(KeyBinding::StylerMenu) => "Styler",
+            SettingKind::Binding(KeyBinding::Partner) => "Switch partner",
+        }
+    }
+
+    fn value(&self, settings: &Settings, rebinding: &Rebinding) -> String {
+        match self {
+            SettingKind::WindowMode => format!("{:?}", settings.window_mode),
+            SettingKind::Scale => format!("{}x", settings.scale),
+            SettingKind::Volume => format!("{:.0}%", settings.volume * 100.),
+            SettingKind::LineColors if settings.palette != Palette::Standard => {
+                format!("{:?} (palette)", settings.palette)
+            }
+            SettingKind::LineColors => format!("{:?}", settings.line_colors),
+            SettingKind::Palette => format!("{:?}", settings.palette),
+            SettingKind::CursorLeave => format!("{:?}", settings.cursor_leave),
+            SettingKind::CursorGrace => format!("{}ms", settings.cursor_grace_ms),
+            SettingKind::ReducedMotion => on_off(settings.reduced_motion),
+            SettingKind::Binding(binding) if rebinding.0 == Some(*binding) => {
+                "Press a key...".into()
+            }
+            SettingKind::Binding(binding) => format!("{:?}", binding.key(&settings.bindings)),
+        }
+    }
+
+    /// Changes the setting by `step`, which is either `1` or `-1`.
+    fn adjust(&self, settings: &mut Settings, rebinding: &mut Rebinding, step: i32) {
+        match self {
+            SettingKind::WindowMode => {
+                settings.window_mode = cycle(&WINDOW_MODES, settings.window_mode, step);
+            }
+            SettingKind::Scale => {
+                settings.scale = settings
+                    .scale
+                    .saturating_add_signed(step)
+                    .clamp(1, Settings::MAX_SCALE);
+            }
+            SettingKind::Volume => {
+                settings.volume = (settings.volume + VOLUME_STEP * step as f32).clamp(0., 1.);
+            }
+            SettingKind::LineColors => {
+                settings.line_colors = cycle(&LINE_COLOR_SCHEMES, settings.line_colors, step);
+            }
+            SettingKind::Palette => {
+                settings.palette = cycle(&PALETTES, settings.palette, step);
+            }
+            SettingKind::CursorLeave => {
+                settings.cursor_leave = cycle(&CURSOR_LEAVE_POLICIES, settings.cursor_leave, step);
+            }
+            SettingKind::CursorGrace => {
+                settings.cursor_grace_ms = settings
+                    .cursor_grace_ms
+                    .saturating_add_signed(GRACE_STEP_MS as i64 * step as i64)
+                    .min(MAX_GRACE_MS);
+            }
+            SettingKind::ReducedMotion => settings.reduced_motion = !settings.reduced_motion,
+            SettingKind::Binding(binding) => rebinding.0 = Some(*binding),
+        }
+    }
+}
+
+fn on_off(value: bool) -> String {
+    if value { "On" } else { "Off" }.into()
+}
+
+/// Picks the value `step` places after `current`, wrapping around.
+fn cycle<T: Copy + PartialEq>(values: &[T], current: T, step: i32) -> T {
+    let index = values
+        .iter()
+        .position(|value| *value == current)
+        .unwrap_or(0) as i32;
+    values[(index + step).rem_euclid(values.len() as i32) as usize]
+}
+
+/// The action waiting for a key to be pressed so that it can be rebound.
+#[derive(Resource, Debug, Default)]
+pub(crate) struct Rebinding(Option<KeyBinding>);
+
+/// Run condition for while the menu is waiting for a key, so that other key bindings can stay out
+/// of the way. There's nothing to rebind without the menu, as when capturing is used on its own.
+pub(crate) fn rebinding_key(rebinding: Option<Res<Rebinding>>) -> bool {
+    rebinding.is_some_and(|rebinding| rebinding.0.is_some())
+}
+
+/// Text showing the current value of a setting.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct SettingValue(SettingKind);
+
+/// A button that changes a setting when pressed.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct AdjustSetting {
+    kind: SettingKind,
+    step: i32,
+}
+
+fn setting_button(kind: SettingKind, step: i32, label: impl Bundle) -> impl Bundle {
+    (
+        Button,
+        AdjustSetting { kind, step },
+        Node {
+            padding: UiRect::horizontal(Val::Px(4.)),
+            ..default()
+        },
+        BackgroundColor(BUTTON_COLOR),
+        children![(label, TextFont::from_font_size(FONT_SIZE))],
+    )
+}
+
+fn spawn_settings_menu(mut commands: Commands) {
+    commands
+        .spawn((
+            Name::from("Settings Menu"),
+            SettingsMenu,
+            Node {
+                position_type: PositionType::Absolute,
+                align_self: AlignSelf::Center,
+                justify_self: JustifySelf::Center,
+                display: Display::Grid,
+                grid_template_columns: vec![GridTrack::auto(); 4],
+                column_gap: Val::Px(6.),
+                row_gap: Val::Px(4.),
+                padding: UiRect::all(Val::Px(12.)),
+                ..default()
+            },
+            BackgroundColor(MENU_BACKGROUND_COLOR),
+            GlobalZIndex(1),
+            Visibility::Hidden,
+        ))
+        .with_children(|menu| {
+            for kind in SettingKind::ALL {
+                menu.spawn((Text::new(kind.label()), TextFont::from_font_size(FONT_SIZE)));
+
+                if let SettingKind::Binding(_) = kind {
+                    menu.spawn(Node::default());
+                    menu.spawn(setting_button(
+                        kind,
+                        0,
+                        (SettingValue(kind), Text::default()),
+                    ));
+                    menu.spawn(Node::default());
+                } else {
+                    menu.spawn(setting_button(kind, -1, Text::new("<")));
+                    menu.spawn((
+                        SettingValue(kind),
+                        Text::default(),
+                        TextFont::from_font_size(FONT_SIZE),
+                    ));
+                    menu.spawn(setting_button(kind, 1, Text::new(">")));
+                }
+            }
+        });
+}
+
+fn toggle_settings_menu(menu: Single<&mut Visibility, With<SettingsMenu>>) {
+    let mut visibility = menu.into_inner();
+    *visibility = match *visibility {
+        Visibility::Hidden => Visibility::Inherited,
+        _ => Visibility::Hidden,
+    };
+}
+
+fn press_setting_buttons(
+    mut settings: ResMut<Settings>,
+    mut rebinding: ResMut<Rebinding>,
+    buttons: Query<(&Interaction, &AdjustSetting), Changed<Interaction>>,
+) {
+    for (interaction, adjust) in buttons {
+        if *interaction == Interaction::Pressed {
+            adjust
+                .kind
+                .adjust(&mut settings, &mut rebinding, adjust.step);
+        }
+    }
+}
+
+fn rebind_key(
+    mut settings: ResMut<Settings>,
+    mut rebinding: ResMut<Rebinding>,
+    input: Res<ButtonInput<KeyCode>>,
+) {
+    let Some(key) = input.get_just_pressed().next().copied() else {
+        return;
+    };
+
+    if let Some(binding) = rebinding.0.take() {
+        if key != KeyCode::Escape {
+            *binding.key_mut(&mut settings.bindings) = key;
+        }
+    }
+}
+
+fn update_setting_values(
+    settings: Res<Settings>,
+    rebinding: Res<Rebinding>,
+    values: Query<(&SettingValue, &mut Text)>,
+) {
+    for (value, mut text) in values {
+        text.0 = value.0.value(&settings, &rebinding);
+    }
+}
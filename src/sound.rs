@@ -0,0 +1,96 @@
+#[cfg(feature = "overworld")]
+mod music;
+
+use crate::capture::decay::CreatureBrokeFree;
+use crate::capture::session::{CaptureSessionChanged, EndReason, SessionState};
+use crate::capture::TakeDamage;
+#[cfg(feature = "overworld")]
+use crate::sound::music::MusicPlugin;
+use crate::DespawnWith;
+use bevy::prelude::*;
+
+/// Plays sound effects for capture events, and music for each level.
+pub struct SoundPlugin;
+impl Plugin for SoundPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Sounds>()
+            .register_type::<DrawingHum>()
+            .init_resource::<Sounds>()
+            .add_systems(Startup, load_sounds)
+            .add_observer(play_session_sounds)
+            .add_observer(play_on::<TakeDamage>(|sounds| &sounds.hit))
+            .add_observer(play_on::<CreatureBrokeFree>(|sounds| &sounds.escape));
+
+        #[cfg(feature = "overworld")]
+        app.add_plugins(MusicPlugin);
+    }
+}
+
+#[derive(Resource, Reflect, Debug, Default)]
+#[reflect(Resource)]
+struct Sounds {
+    /// Loops for as long as a capture line is being drawn.
+    hum: Handle<AudioSource>,
+    chime: Handle<AudioSource>,
+    line_break: Handle<AudioSource>,
+    hit: Handle<AudioSource>,
+    fanfare: Handle<AudioSource>,
+
+    /// Played when a looped creature's progress drains away and it breaks free.
+    escape: Handle<AudioSource>,
+}
+
+/// The hum of a capture line being drawn, despawned along with its session.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct DrawingHum;
+
+fn load_sounds(asset_server: Res<AssetServer>, mut sounds: ResMut<Sounds>) {
+    sounds.hum = asset_server.load("audio/hum.wav");
+    sounds.chime = asset_server.load("audio/chime.wav");
+    sounds.line_break = asset_server.load("audio/break.wav");
+    sounds.hit = asset_server.load("audio/hit.wav");
+    sounds.fanfare = asset_server.load("audio/fanfare.wav");
+    sounds.escape = asset_server.load("audio/escape.wav");
+}
+
+fn play_session_sounds(
+    changed: Trigger<CaptureSessionChanged>,
+    mut commands: Commands,
+    sounds: Res<Sounds>,
+) {
+    let sound = match &changed.state {
+        SessionState::Started => {
+            commands.spawn((
+                Name::from("Drawing Hum"),
+                DrawingHum,
+                AudioPlayer(sounds.hum.clone()),
+                PlaybackSettings::LOOP,
+                DespawnWith(changed.session),
+            ));
+            return;
+        }
+        SessionState::LoopClosed => &sounds.chime,
+        SessionState::Ended {
+            reason: EndReason::Hit,
+        } => &sounds.line_break,
+        SessionState::Ended {
+            reason: EndReason::Success { .. },
+        } => &sounds.fanfare,
+        _ => return,
+    };
+
+    commands.spawn((AudioPlayer(sound.clone()), PlaybackSettings::DESPAWN));
+}
+
+/// Observer that plays a one-off sound whenever `E` is triggered.
+fn play_on<E: Event>(
+    sound: fn(&Sounds) -> &Handle<AudioSource>,
+) -> impl Fn(Trigger<E>, Commands, Res<Sounds>) {
+    move |_, mut commands, sounds| {
+        commands.spawn((
+            AudioPlayer(sound(&sounds).clone()),
+            PlaybackSettings::DESPAWN,
+        ));
+    }
+}
@@ -0,0 +1,127 @@
+use bevy::audio::Volume;
+use bevy::prelude::*;
+use bevy_ecs_ldtk::prelude::*;
+use std::time::Duration;
+
+/// How long the old track takes to fade out while the new one fades in.
+const CROSSFADE: Duration = Duration::from_secs(2);
+
+/// Plays the track set in each LDtk level's `Music` field, crossfading between levels.
+pub(super) struct MusicPlugin;
+impl Plugin for MusicPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Music>().add_systems(
+            Update,
+            (change_music.run_if(on_event::<LevelEvent>), crossfade_music).chain(),
+        );
+    }
+}
+
+/// A looping background track.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct Music {
+    /// The asset path of the track, taken from the level.
+    path: String,
+    fade: Timer,
+
+    /// Whether the track is on its way out, to be despawned once it's silent.
+    fading_out: bool,
+}
+
+impl Music {
+    fn new(path: String) -> Self {
+        Self {
+            path,
+            fade: Timer::new(CROSSFADE, TimerMode::Once),
+            fading_out: false,
+        }
+    }
+
+    /// How loud the track is relative to the global volume, from `0.` to `1.`.
+    fn loudness(&self) -> f32 {
+        if self.fading_out {
+            self.fade.fraction_remaining()
+        } else {
+            self.fade.fraction()
+        }
+    }
+}
+
+fn change_music(
+    mut commands: Commands,
+    mut events: EventReader<LevelEvent>,
+    asset_server: Res<AssetServer>,
+    projects: Res<Assets<LdtkProject>>,
+    project: Single<&LdtkProjectHandle>,
+    playing: Query<&mut Music>,
+) {
+    let Some(project) = projects.get(&project.handle) else {
+        return;
+    };
+
+    let Some(level) = events
+        .read()
+        .filter_map(|event| match event {
+            LevelEvent::Spawned(iid) => project.get_raw_level_by_iid(&iid.to_string()),
+            _ => None,
+        })
+        .last()
+    else {
+        return;
+    };
+
+    let track = match level.get_maybe_file_path_field("Music") {
+        Ok(track) => track.clone(),
+        Err(e) => {
+            debug!("Level {} has no music: {e}", level.identifier);
+            None
+        }
+    };
+
+    let mut already_playing = false;
+    for mut music in playing {
+        if music.fading_out {
+            continue;
+        }
+        if Some(&music.path) == track.as_ref() {
+            already_playing = true;
+            continue;
+        }
+
+        // Fade out from wherever the fade in got to, rather than jumping to full volume.
+        let elapsed = music.fade.remaining();
+        music.fading_out = true;
+        music.fade.reset();
+        music.fade.set_elapsed(elapsed);
+    }
+
+    if let Some(track) = track.filter(|_| !already_playing) {
+        commands.spawn((
+            Name::from("Music"),
+            AudioPlayer::new(asset_server.load(&track)),
+            PlaybackSettings::LOOP.with_volume(Volume::SILENT),
+            Music::new(track),
+        ));
+    }
+}
+
+fn crossfade_music(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    global_volume: Res<GlobalVolume>,
+    tracks: Query<(Entity, &mut Music, Option<&mut AudioSink>)>,
+) {
+    for (entity, mut music, sink) in tracks {
+        music.fade.tick(time.delta());
+
+        // Sinks don't pick up the global volume after they are created, so it is applied here.
+        if let Some(mut sink) = sink {
+            sink.set_volume(global_volume.volume * Volume::Linear(music.loudness()));
+        }
+
+        if music.fading_out && music.fade.finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
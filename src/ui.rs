@@ -2,6 +2,14 @@ mod capture;
 
 use bevy::prelude::*;
 
+/// Behind each menu, dark enough to read over the game.
+pub(crate) const MENU_BACKGROUND_COLOR: Color = Color::linear_rgba(0.066, 0.060, 0.060, 0.924);
+
+pub(crate) const BUTTON_COLOR: Color = Color::linear_rgba(0.2, 0.2, 0.2, 1.);
+
+/// The size of the text in menus.
+pub(crate) const FONT_SIZE: f32 = 10.;
+
 pub struct UiPlugin;
 impl Plugin for UiPlugin {
     fn build(&self, app: &mut App) {